@@ -1,4 +1,4 @@
-use criterion::{criterion_group, criterion_main, Criterion};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use std::collections::HashMap;
 use truth_table_rs::FormulaParser;
 
@@ -30,6 +30,15 @@ fn eval_benchmark(c: &mut Criterion) {
     .collect::<HashMap<_, _>>();
 
     c.bench_function("eval_formula", |b| b.iter(|| formula.eval(&vars)));
+
+    let compiled = formula.compile();
+    let values = [true, false, true, false, true];
+    c.bench_function("eval_compiled_slice", |b| {
+        b.iter(|| compiled.eval(black_box(&values)))
+    });
+    c.bench_function("eval_compiled_bits", |b| {
+        b.iter(|| compiled.eval_bits(black_box(0b10101)))
+    });
 }
 
 fn print_truth_table_benchmark(c: &mut Criterion) {
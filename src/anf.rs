@@ -0,0 +1,136 @@
+use std::fmt;
+
+use crate::{Formula, Node};
+
+/// Algebraic normal form: an exclusive or of products of variables, also
+/// known as the Zhegalkin polynomial of a function.
+///
+/// Every function has exactly one such polynomial, so two formulas over the
+/// same variables are equivalent exactly when their polynomials are equal.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Anf {
+    variables: Vec<String>,
+    /// Monomials as variable masks, numbered like minterms: variable `j` is
+    /// bit `n - 1 - j`. Ordered by degree, then by variable order.
+    monomials: Vec<u64>,
+}
+
+impl Anf {
+    /// The variables of the polynomial, in truth table order.
+    pub fn variables(&self) -> &[String] {
+        &self.variables
+    }
+
+    /// The variables of a monomial, in order.
+    fn monomial(&self, mask: u64) -> Vec<&str> {
+        let n = self.variables.len();
+        (0..n)
+            .filter(|&j| mask >> (n - 1 - j) & 1 == 1)
+            .map(|j| self.variables[j].as_str())
+            .collect()
+    }
+
+    /// The monomials of the polynomial; the empty monomial is the constant 1.
+    pub fn monomials(&self) -> Vec<Vec<&str>> {
+        self.monomials.iter().map(|&m| self.monomial(m)).collect()
+    }
+
+    /// The number of variables in the largest monomial, or 0 for constants.
+    pub fn degree(&self) -> usize {
+        self.monomials
+            .iter()
+            .map(|m| m.count_ones() as usize)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Whether the polynomial has the constant term 1.
+    pub fn constant(&self) -> bool {
+        self.monomials.first() == Some(&0)
+    }
+
+    /// Whether the degree is at most 1, i.e. the function is an exclusive or
+    /// of variables, possibly negated.
+    pub fn is_affine(&self) -> bool {
+        self.degree() <= 1
+    }
+
+    /// Whether the function is affine without a constant term.
+    pub fn is_linear(&self) -> bool {
+        self.is_affine() && !self.constant()
+    }
+
+    /// Converts the polynomial back into a formula, writing `x ⊕ y` as
+    /// `¬(x ⇔ y)`.
+    pub fn to_formula(&self) -> Formula {
+        let product = |mask: u64| {
+            self.monomial(mask)
+                .into_iter()
+                .map(|v| Node::Atom(v.to_string()))
+                .reduce(|acc, n| Node::And(Box::new(acc), Box::new(n)))
+                .unwrap_or(Node::Value(true))
+        };
+        let root = self
+            .monomials
+            .iter()
+            .map(|&m| product(m))
+            .reduce(|acc, n| Node::Not(Box::new(Node::Iff(Box::new(acc), Box::new(n)))))
+            .unwrap_or(Node::Value(false));
+        Formula::from_root(root)
+    }
+}
+
+/// Prints the polynomial as e.g. `1 ⊕ a ⊕ a·b`, or `0` if it is empty.
+impl fmt::Display for Anf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.monomials.is_empty() {
+            return write!(f, "0");
+        }
+        for (i, &mask) in self.monomials.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ⊕ ")?;
+            }
+            if mask == 0 {
+                write!(f, "1")?;
+            } else {
+                write!(f, "{}", self.monomial(mask).join("·"))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Applies the binary Möbius transform in place, turning a truth vector into
+/// the coefficients of its polynomial. The transform is its own inverse.
+fn mobius(values: &mut [bool]) {
+    let mut bit = 1;
+    while bit < values.len() {
+        for i in 0..values.len() {
+            if i & bit != 0 {
+                values[i] ^= values[i ^ bit];
+            }
+        }
+        bit <<= 1;
+    }
+}
+
+impl Formula {
+    /// Computes the algebraic normal form with the Möbius transform of the
+    /// truth vector.
+    pub fn to_anf(&self) -> Anf {
+        let variables = self.sorted_variables();
+        let mut coefficients = self.truth_vector();
+        mobius(&mut coefficients);
+
+        let mut monomials: Vec<u64> = (0..coefficients.len() as u64)
+            .filter(|&m| coefficients[m as usize])
+            .collect();
+        // A higher mask puts its first variable earlier, so reversing the
+        // mask order within a degree gives variable order
+        monomials.sort_unstable_by_key(|&m| (m.count_ones(), std::cmp::Reverse(m)));
+        Anf {
+            variables: variables.into_iter().map(String::from).collect(),
+            monomials,
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use std::fmt::Write;
+
+use crate::{bdd::escape, Formula, Node};
+
+impl Node {
+    /// The label of this node in tree renderings, using the same symbols as
+    /// [`Display`](std::fmt::Display).
+    fn label(&self) -> String {
+        match self {
+            Node::And(..) => "∧".to_string(),
+            Node::Or(..) => "∨".to_string(),
+            Node::Not(_) => "¬".to_string(),
+            Node::If(..) => "⇒".to_string(),
+            Node::Iff(..) => "⇔".to_string(),
+            Node::Atom(s) => s.clone(),
+            Node::Value(b) => if *b { "T" } else { "F" }.to_string(),
+        }
+    }
+
+    fn operands(&self) -> Vec<&Node> {
+        match self {
+            Node::And(left, right)
+            | Node::Or(left, right)
+            | Node::If(left, right)
+            | Node::Iff(left, right) => vec![left, right],
+            Node::Not(operand) => vec![operand],
+            Node::Atom(_) | Node::Value(_) => Vec::new(),
+        }
+    }
+}
+
+/// Writes `root` and its descendants one per line, in pre-order.
+fn write_tree(out: &mut String, root: &Node) {
+    // Each node comes with the text before its label and the prefix of the
+    // lines below it
+    let mut pending = vec![(root, String::new(), String::new())];
+    while let Some((node, line_prefix, prefix)) = pending.pop() {
+        writeln!(out, "{}{}", line_prefix, node.label()).unwrap();
+        let operands = node.operands();
+        let count = operands.len();
+        for (i, operand) in operands.into_iter().enumerate().rev() {
+            let last = i + 1 == count;
+            let (branch, indent) = if last {
+                ("└─ ", "   ")
+            } else {
+                ("├─ ", "│  ")
+            };
+            pending.push((
+                operand,
+                format!("{}{}", prefix, branch),
+                format!("{}{}", prefix, indent),
+            ));
+        }
+    }
+}
+
+impl Formula {
+    /// Renders the parse tree as a Graphviz DOT graph.
+    ///
+    /// Nodes are labelled with their operator symbol, atom name or constant,
+    /// and operands are laid out left to right in source order.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "digraph ast {{").unwrap();
+        writeln!(out, "  ordering=out;").unwrap();
+        let mut stack = vec![(&self.root, None)];
+        let mut next_id = 0;
+        while let Some((node, parent)) = stack.pop() {
+            let id = next_id;
+            next_id += 1;
+            let shape = match node {
+                Node::Atom(_) | Node::Value(_) => "box",
+                _ => "ellipse",
+            };
+            writeln!(
+                out,
+                "  n{} [shape={}, label=\"{}\"];",
+                id,
+                shape,
+                escape(&node.label())
+            )
+            .unwrap();
+            if let Some(parent) = parent {
+                writeln!(out, "  n{} -> n{};", parent, id).unwrap();
+            }
+            for operand in node.operands().into_iter().rev() {
+                stack.push((operand, Some(id)));
+            }
+        }
+        writeln!(out, "}}").unwrap();
+        out
+    }
+
+    /// Renders the parse tree as indented text, one node per line:
+    ///
+    /// ```text
+    /// ∨
+    /// ├─ ∧
+    /// │  ├─ a
+    /// │  └─ b
+    /// └─ ¬
+    ///    └─ c
+    /// ```
+    ///
+    /// Every line is indented by its depth, so the text grows with the
+    /// square of the nesting depth.
+    pub fn to_ascii_tree(&self) -> String {
+        let mut out = String::new();
+        write_tree(&mut out, &self.root);
+        out
+    }
+}
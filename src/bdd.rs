@@ -0,0 +1,509 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{Formula, Node, Visitor};
+
+mod dot;
+mod reorder;
+
+pub(crate) use dot::escape;
+pub use reorder::{ReorderReport, Reordering};
+
+/// Handle to a node owned by a [`BddManager`].
+///
+/// Handles are canonical: two functions built in the same manager are
+/// equivalent exactly when their handles are equal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Bdd(u32);
+
+impl Bdd {
+    pub const FALSE: Bdd = Bdd(0);
+    pub const TRUE: Bdd = Bdd(1);
+
+    pub fn is_terminal(self) -> bool {
+        self.0 < 2
+    }
+
+    fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// Binary connectives for [`BddManager::apply`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BddOp {
+    And,
+    Or,
+    Xor,
+    If,
+    Iff,
+}
+
+impl BddOp {
+    fn eval(self, a: bool, b: bool) -> bool {
+        match self {
+            BddOp::And => a && b,
+            BddOp::Or => a || b,
+            BddOp::Xor => a != b,
+            BddOp::If => !a || b,
+            BddOp::Iff => a == b,
+        }
+    }
+
+    /// Returns the result without recursing when an identity decides it,
+    /// such as `f ∧ F = F` or `f ⇔ f = T`.
+    fn shortcut(self, f: Bdd, g: Bdd) -> Option<Bdd> {
+        match self {
+            BddOp::And if f == Bdd::FALSE || g == Bdd::FALSE => Some(Bdd::FALSE),
+            BddOp::And if f == Bdd::TRUE || f == g => Some(g),
+            BddOp::And if g == Bdd::TRUE => Some(f),
+            BddOp::Or if f == Bdd::TRUE || g == Bdd::TRUE => Some(Bdd::TRUE),
+            BddOp::Or if f == Bdd::FALSE || f == g => Some(g),
+            BddOp::Or if g == Bdd::FALSE => Some(f),
+            BddOp::Xor if f == g => Some(Bdd::FALSE),
+            BddOp::Xor if f == Bdd::FALSE => Some(g),
+            BddOp::Xor if g == Bdd::FALSE => Some(f),
+            BddOp::If if f == Bdd::FALSE || g == Bdd::TRUE || f == g => Some(Bdd::TRUE),
+            BddOp::If if f == Bdd::TRUE => Some(g),
+            BddOp::Iff if f == g => Some(Bdd::TRUE),
+            BddOp::Iff if f == Bdd::TRUE => Some(g),
+            BddOp::Iff if g == Bdd::TRUE => Some(f),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct BddNode {
+    pub var: usize,
+    pub low: Bdd,
+    pub high: Bdd,
+}
+
+/// Owner of reduced ordered binary decision diagrams.
+///
+/// Nodes are hash-consed through a unique table, so every function has exactly
+/// one node, and results of [`BddManager::apply`] are memoized in a computed
+/// table. Variables are identified by name and tested in the manager's
+/// variable order, top to bottom.
+pub struct BddManager {
+    pub(crate) names: Vec<String>,
+    pub(crate) indices: HashMap<String, usize>,
+    pub(crate) level_of_var: Vec<usize>,
+    pub(crate) var_of_level: Vec<usize>,
+    pub(crate) nodes: Vec<BddNode>,
+    pub(crate) unique: HashMap<BddNode, Bdd>,
+    computed: HashMap<(BddOp, Bdd, Bdd), Bdd>,
+    /// Diagrams built from formulas, measured by automatic reordering.
+    roots: Vec<Bdd>,
+    reorder_threshold: Option<usize>,
+    reorder_reports: Vec<ReorderReport>,
+}
+
+impl Default for BddManager {
+    fn default() -> Self {
+        BddManager::new()
+    }
+}
+
+/// Multiplies `count` by `2^skipped`, or returns `None` on overflow.
+fn shift(count: u128, skipped: usize) -> Option<u128> {
+    if count == 0 {
+        Some(0)
+    } else if skipped <= count.leading_zeros() as usize {
+        Some(count << skipped)
+    } else {
+        None
+    }
+}
+
+impl BddManager {
+    /// Creates a manager whose variables are added on demand, each below the
+    /// existing ones.
+    pub fn new() -> BddManager {
+        // Terminals are stored as nodes past the last level
+        let terminal = BddNode {
+            var: usize::MAX,
+            low: Bdd::FALSE,
+            high: Bdd::FALSE,
+        };
+        BddManager {
+            names: Vec::new(),
+            indices: HashMap::new(),
+            level_of_var: Vec::new(),
+            var_of_level: Vec::new(),
+            nodes: vec![terminal, terminal],
+            unique: HashMap::new(),
+            computed: HashMap::new(),
+            roots: Vec::new(),
+            reorder_threshold: None,
+            reorder_reports: Vec::new(),
+        }
+    }
+
+    /// Creates a manager with the given variable order, top to bottom.
+    pub fn with_order<S: AsRef<str>>(order: impl IntoIterator<Item = S>) -> BddManager {
+        let mut manager = BddManager::new();
+        for name in order {
+            manager.var_index(name.as_ref());
+        }
+        manager
+    }
+
+    /// Returns the variable names in their current order, top to bottom.
+    pub fn order(&self) -> Vec<&str> {
+        self.var_of_level
+            .iter()
+            .map(|&v| self.names[v].as_str())
+            .collect()
+    }
+
+    pub fn num_vars(&self) -> usize {
+        self.names.len()
+    }
+
+    pub(crate) fn var_index(&mut self, name: &str) -> usize {
+        if let Some(&index) = self.indices.get(name) {
+            return index;
+        }
+        let index = self.names.len();
+        self.names.push(name.to_string());
+        self.indices.insert(name.to_string(), index);
+        self.level_of_var.push(self.var_of_level.len());
+        self.var_of_level.push(index);
+        index
+    }
+
+    pub(crate) fn node(&self, f: Bdd) -> BddNode {
+        self.nodes[f.index()]
+    }
+
+    /// Level of the variable tested by `f`, or the number of variables for a
+    /// terminal.
+    pub(crate) fn level(&self, f: Bdd) -> usize {
+        if f.is_terminal() {
+            self.var_of_level.len()
+        } else {
+            self.level_of_var[self.node(f).var]
+        }
+    }
+
+    pub(crate) fn mk(&mut self, var: usize, low: Bdd, high: Bdd) -> Bdd {
+        if low == high {
+            return low;
+        }
+        let node = BddNode { var, low, high };
+        if let Some(&f) = self.unique.get(&node) {
+            return f;
+        }
+        let f = Bdd(self.nodes.len() as u32);
+        self.nodes.push(node);
+        self.unique.insert(node, f);
+        f
+    }
+
+    pub fn constant(&self, value: bool) -> Bdd {
+        if value {
+            Bdd::TRUE
+        } else {
+            Bdd::FALSE
+        }
+    }
+
+    /// Returns the function of a single variable, adding it if needed.
+    pub fn var(&mut self, name: &str) -> Bdd {
+        let var = self.var_index(name);
+        self.mk(var, Bdd::FALSE, Bdd::TRUE)
+    }
+
+    /// Name of the variable tested by a non-terminal node.
+    pub fn var_name(&self, f: Bdd) -> Option<&str> {
+        (!f.is_terminal()).then(|| self.names[self.node(f).var].as_str())
+    }
+
+    /// The `(low, high)` children of a non-terminal node.
+    pub fn children(&self, f: Bdd) -> Option<(Bdd, Bdd)> {
+        (!f.is_terminal()).then(|| {
+            let node = self.node(f);
+            (node.low, node.high)
+        })
+    }
+
+    /// Cofactors of `f` with respect to the variable at `level`.
+    fn cofactors(&self, f: Bdd, level: usize) -> (Bdd, Bdd) {
+        if self.level(f) == level {
+            let node = self.node(f);
+            (node.low, node.high)
+        } else {
+            (f, f)
+        }
+    }
+
+    pub fn apply(&mut self, op: BddOp, f: Bdd, g: Bdd) -> Bdd {
+        if f.is_terminal() && g.is_terminal() {
+            return self.constant(op.eval(f == Bdd::TRUE, g == Bdd::TRUE));
+        }
+        if let Some(result) = op.shortcut(f, g) {
+            return result;
+        }
+        let key = (op, f, g);
+        if let Some(&result) = self.computed.get(&key) {
+            return result;
+        }
+
+        let level = self.level(f).min(self.level(g));
+        let (f0, f1) = self.cofactors(f, level);
+        let (g0, g1) = self.cofactors(g, level);
+        let low = self.apply(op, f0, g0);
+        let high = self.apply(op, f1, g1);
+        let result = self.mk(self.var_of_level[level], low, high);
+        self.computed.insert(key, result);
+        result
+    }
+
+    pub fn not(&mut self, f: Bdd) -> Bdd {
+        self.apply(BddOp::Xor, f, Bdd::TRUE)
+    }
+
+    /// Fixes the variable `name` to `value` in `f`.
+    pub fn restrict(&mut self, f: Bdd, name: &str, value: bool) -> Bdd {
+        let Some(&var) = self.indices.get(name) else {
+            return f;
+        };
+        let level = self.level_of_var[var];
+        let mut memo = HashMap::new();
+        self.restrict_rec(f, level, value, &mut memo)
+    }
+
+    fn restrict_rec(
+        &mut self,
+        f: Bdd,
+        level: usize,
+        value: bool,
+        memo: &mut HashMap<Bdd, Bdd>,
+    ) -> Bdd {
+        if self.level(f) > level {
+            return f;
+        }
+        let node = self.node(f);
+        if self.level(f) == level {
+            return if value { node.high } else { node.low };
+        }
+        if let Some(&result) = memo.get(&f) {
+            return result;
+        }
+        let low = self.restrict_rec(node.low, level, value, memo);
+        let high = self.restrict_rec(node.high, level, value, memo);
+        let result = self.mk(node.var, low, high);
+        memo.insert(f, result);
+        result
+    }
+
+    /// Existentially quantifies `names` out of `f`.
+    pub fn exists(&mut self, f: Bdd, names: &[&str]) -> Bdd {
+        self.quantify(f, names, BddOp::Or)
+    }
+
+    /// Universally quantifies `names` out of `f`.
+    pub fn forall(&mut self, f: Bdd, names: &[&str]) -> Bdd {
+        self.quantify(f, names, BddOp::And)
+    }
+
+    fn quantify(&mut self, f: Bdd, names: &[&str], op: BddOp) -> Bdd {
+        let vars: HashSet<usize> = names
+            .iter()
+            .filter_map(|name| self.indices.get(*name).copied())
+            .collect();
+        let mut memo = HashMap::new();
+        self.quantify_rec(f, &vars, op, &mut memo)
+    }
+
+    fn quantify_rec(
+        &mut self,
+        f: Bdd,
+        vars: &HashSet<usize>,
+        op: BddOp,
+        memo: &mut HashMap<Bdd, Bdd>,
+    ) -> Bdd {
+        if f.is_terminal() {
+            return f;
+        }
+        if let Some(&result) = memo.get(&f) {
+            return result;
+        }
+        let node = self.node(f);
+        let low = self.quantify_rec(node.low, vars, op, memo);
+        let high = self.quantify_rec(node.high, vars, op, memo);
+        let result = if vars.contains(&node.var) {
+            self.apply(op, low, high)
+        } else {
+            self.mk(node.var, low, high)
+        };
+        memo.insert(f, result);
+        result
+    }
+
+    /// Counts the assignments of all the manager's variables satisfying `f`,
+    /// or returns `None` if the count does not fit in a `u128`, which can
+    /// only happen with 128 variables or more.
+    pub fn sat_count(&self, f: Bdd) -> Option<u128> {
+        let mut memo = HashMap::new();
+        shift(self.count_rec(f, &mut memo)?, self.level(f))
+    }
+
+    /// Counts the assignments of the variables at or below the level of `f`.
+    fn count_rec(&self, f: Bdd, memo: &mut HashMap<Bdd, Option<u128>>) -> Option<u128> {
+        if f.is_terminal() {
+            return Some(u128::from(f == Bdd::TRUE));
+        }
+        if let Some(&count) = memo.get(&f) {
+            return count;
+        }
+        let node = self.node(f);
+        let level = self.level(f);
+        let mut count = |g: Bdd| shift(self.count_rec(g, memo)?, self.level(g) - level - 1);
+        let total = count(node.low)
+            .zip(count(node.high))
+            .and_then(|(low, high)| low.checked_add(high));
+        memo.insert(f, total);
+        total
+    }
+
+    /// Returns a satisfying assignment of all the manager's variables, with
+    /// variables `f` does not depend on set to false.
+    pub fn any_model(&self, f: Bdd) -> Option<HashMap<String, bool>> {
+        if f == Bdd::FALSE {
+            return None;
+        }
+        let mut model: HashMap<_, _> = self.names.iter().map(|n| (n.clone(), false)).collect();
+        let mut f = f;
+        while !f.is_terminal() {
+            let node = self.node(f);
+            let value = node.high != Bdd::FALSE;
+            model.insert(self.names[node.var].clone(), value);
+            f = if value { node.high } else { node.low };
+        }
+        Some(model)
+    }
+
+    /// Returns every path to the true terminal as a partial assignment.
+    ///
+    /// The cubes are disjoint and together cover exactly the models of `f`.
+    pub fn cubes(&self, f: Bdd) -> Vec<HashMap<String, bool>> {
+        let mut cubes = Vec::new();
+        let mut stack: Vec<(Bdd, Vec<(usize, bool)>)> = vec![(f, Vec::new())];
+        while let Some((f, path)) = stack.pop() {
+            if f == Bdd::TRUE {
+                cubes.push(
+                    path.into_iter()
+                        .map(|(var, value)| (self.names[var].clone(), value))
+                        .collect(),
+                );
+                continue;
+            }
+            if f == Bdd::FALSE {
+                continue;
+            }
+            let node = self.node(f);
+            let mut low = path.clone();
+            low.push((node.var, false));
+            let mut high = path;
+            high.push((node.var, true));
+            stack.push((node.low, low));
+            stack.push((node.high, high));
+        }
+        cubes
+    }
+
+    /// Evaluates `f` under an assignment of its variables.
+    pub fn eval(&self, f: Bdd, vars: &HashMap<String, bool>) -> Option<bool> {
+        let mut f = f;
+        while !f.is_terminal() {
+            let node = self.node(f);
+            f = if *vars.get(&self.names[node.var])? {
+                node.high
+            } else {
+                node.low
+            };
+        }
+        Some(f == Bdd::TRUE)
+    }
+
+    /// Counts the nodes reachable from `f`, terminals included.
+    pub fn node_count(&self, f: Bdd) -> usize {
+        self.reachable(&[f]).len()
+    }
+
+    pub(crate) fn reachable(&self, roots: &[Bdd]) -> HashSet<Bdd> {
+        let mut seen = HashSet::new();
+        let mut stack = roots.to_vec();
+        while let Some(f) = stack.pop() {
+            if !seen.insert(f) || f.is_terminal() {
+                continue;
+            }
+            let node = self.node(f);
+            stack.push(node.low);
+            stack.push(node.high);
+        }
+        seen
+    }
+
+    /// Builds the BDD of `root` bottom-up, walking the tree with an explicit
+    /// stack.
+    fn build(&mut self, root: &Node) -> Bdd {
+        struct Builder<'m> {
+            manager: &'m mut BddManager,
+            built: Vec<Bdd>,
+        }
+
+        impl<'a> Visitor<'a> for Builder<'_> {
+            fn post(&mut self, node: &'a Node) {
+                let manager = &mut *self.manager;
+                let f = match node {
+                    Node::Atom(name) => manager.var(name),
+                    Node::Value(b) => manager.constant(*b),
+                    Node::Not(_) => {
+                        let operand = self.built.pop().unwrap();
+                        manager.not(operand)
+                    }
+                    _ => {
+                        let right = self.built.pop().unwrap();
+                        let left = self.built.pop().unwrap();
+                        let op = match node {
+                            Node::And(_, _) => BddOp::And,
+                            Node::Or(_, _) => BddOp::Or,
+                            Node::If(_, _) => BddOp::If,
+                            _ => BddOp::Iff,
+                        };
+                        manager.apply(op, left, right)
+                    }
+                };
+                self.built.push(f);
+            }
+        }
+
+        let mut builder = Builder {
+            manager: self,
+            built: Vec::new(),
+        };
+        root.walk(&mut builder);
+        builder.built.pop().unwrap()
+    }
+}
+
+impl Formula {
+    /// Builds the formula's BDD in `manager`.
+    ///
+    /// Variables the manager does not know yet are added below the existing
+    /// ones in truth table order, so a fresh [`BddManager::new`] uses the same
+    /// order as the truth table. Use [`BddManager::with_order`] to override it,
+    /// or [`BddManager::set_auto_reorder`] to let the manager pick one.
+    pub fn to_bdd(&self, manager: &mut BddManager) -> Bdd {
+        for var in self.sorted_variables() {
+            manager.var_index(var);
+        }
+        let root = manager.build(&self.root);
+        manager.roots.push(root);
+        manager.maybe_reorder();
+        root
+    }
+}
@@ -0,0 +1,57 @@
+use std::fmt::Write;
+
+use super::{Bdd, BddManager};
+
+/// Escapes a label for use inside a double-quoted DOT string.
+pub(crate) fn escape(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+impl BddManager {
+    /// Renders the diagrams reachable from `roots` as a Graphviz DOT graph.
+    ///
+    /// Each root is introduced by a plain-text node carrying its label, e.g.
+    /// the formula it was built from. Decision nodes show their variable name
+    /// and are ranked by level; solid edges lead to the high child, dashed
+    /// edges to the low one, and the terminals are drawn as boxes.
+    pub fn to_dot(&self, roots: &[(&str, Bdd)]) -> String {
+        let ids: Vec<Bdd> = roots.iter().map(|&(_, f)| f).collect();
+        let mut nodes: Vec<Bdd> = self.reachable(&ids).into_iter().collect();
+        nodes.sort_unstable();
+
+        let mut out = String::new();
+        writeln!(out, "digraph bdd {{").unwrap();
+        for (i, (label, f)) in roots.iter().enumerate() {
+            writeln!(
+                out,
+                "  r{} [shape=plaintext, label=\"{}\"];",
+                i,
+                escape(label)
+            )
+            .unwrap();
+            writeln!(out, "  r{} -> n{};", i, f.0).unwrap();
+        }
+
+        let mut levels = vec![Vec::new(); self.num_vars()];
+        for &f in &nodes {
+            if f.is_terminal() {
+                let label = if f == Bdd::TRUE { "T" } else { "F" };
+                writeln!(out, "  n{} [shape=box, label=\"{}\"];", f.0, label).unwrap();
+                continue;
+            }
+            let node = self.node(f);
+            levels[self.level(f)].push(f);
+            let name = escape(&self.names[node.var]);
+            writeln!(out, "  n{} [shape=circle, label=\"{}\"];", f.0, name).unwrap();
+            writeln!(out, "  n{} -> n{};", f.0, node.high.0).unwrap();
+            writeln!(out, "  n{} -> n{} [style=dashed];", f.0, node.low.0).unwrap();
+        }
+
+        for level in levels.iter().filter(|l| l.len() > 1) {
+            let ids: Vec<_> = level.iter().map(|f| format!("n{}", f.0)).collect();
+            writeln!(out, "  {{ rank=same; {}; }}", ids.join("; ")).unwrap();
+        }
+        writeln!(out, "}}").unwrap();
+        out
+    }
+}
@@ -0,0 +1,178 @@
+use super::{Bdd, BddManager, BddNode};
+
+/// Dynamic variable reordering strategies.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Reordering {
+    /// Moves each variable through every level and keeps the best position,
+    /// largest variables first (Rudell's sifting).
+    Sifting,
+    /// Tries every permutation of each window of three adjacent levels.
+    Window3,
+}
+
+/// Outcome of a reordering pass.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReorderReport {
+    /// Nodes reachable from the roots before reordering, terminals included.
+    pub before: usize,
+    /// Nodes reachable from the roots after reordering.
+    pub after: usize,
+    /// The resulting variable order, top to bottom.
+    pub order: Vec<String>,
+}
+
+impl BddManager {
+    /// Swaps the variables at `level` and `level + 1` in place.
+    ///
+    /// Every handle keeps denoting the same function: nodes testing the upper
+    /// variable are rewritten to test the lower one first.
+    pub(crate) fn swap_levels(&mut self, level: usize) {
+        let x = self.var_of_level[level];
+        let y = self.var_of_level[level + 1];
+        let x_nodes: Vec<usize> = (2..self.nodes.len())
+            .filter(|&i| self.nodes[i].var == x)
+            .collect();
+
+        self.var_of_level.swap(level, level + 1);
+        self.level_of_var[x] = level + 1;
+        self.level_of_var[y] = level;
+
+        for i in x_nodes {
+            let node = self.nodes[i];
+            let cofactors = |f: Bdd| {
+                if !f.is_terminal() && self.nodes[f.index()].var == y {
+                    let child = self.nodes[f.index()];
+                    (child.low, child.high)
+                } else {
+                    (f, f)
+                }
+            };
+            let (f00, f01) = cofactors(node.low);
+            let (f10, f11) = cofactors(node.high);
+            if f00 == f01 && f10 == f11 {
+                // Neither child tests y, so the node is unaffected
+                continue;
+            }
+            let low = self.mk(x, f00, f10);
+            let high = self.mk(x, f01, f11);
+            let rewritten = BddNode { var: y, low, high };
+            let id = self.unique.remove(&node).unwrap();
+            self.nodes[i] = rewritten;
+            self.unique.insert(rewritten, id);
+        }
+    }
+
+    fn live_size(&self, roots: &[Bdd]) -> usize {
+        self.reachable(roots).len()
+    }
+
+    /// Moves the variable at `from` to level `to` by adjacent swaps, updating
+    /// `best` with the smallest size seen and the level it was seen at.
+    fn sift_to(&mut self, roots: &[Bdd], from: usize, to: usize, best: &mut (usize, usize)) {
+        let mut level = from;
+        while level != to {
+            if to > level {
+                self.swap_levels(level);
+                level += 1;
+            } else {
+                self.swap_levels(level - 1);
+                level -= 1;
+            }
+            let size = self.live_size(roots);
+            if size < best.0 {
+                *best = (size, level);
+            }
+        }
+    }
+
+    fn sift(&mut self, roots: &[Bdd]) {
+        let mut counts = vec![0; self.names.len()];
+        for f in self.reachable(roots) {
+            if !f.is_terminal() {
+                counts[self.node(f).var] += 1;
+            }
+        }
+        let mut vars: Vec<usize> = (0..self.names.len()).collect();
+        vars.sort_by_key(|&v| std::cmp::Reverse(counts[v]));
+
+        let bottom = self.names.len() - 1;
+        for var in vars {
+            let start = self.level_of_var[var];
+            let mut best = (self.live_size(roots), start);
+            // Visit the closer end first to keep intermediate diagrams small
+            let (first, second) = if start > bottom - start {
+                (bottom, 0)
+            } else {
+                (0, bottom)
+            };
+            self.sift_to(roots, start, first, &mut best);
+            self.sift_to(roots, first, second, &mut best);
+            let best_level = best.1;
+            self.sift_to(roots, second, best_level, &mut best);
+        }
+    }
+
+    fn window3(&mut self, roots: &[Bdd]) {
+        // Six swaps alternating between the two pairs visit every permutation
+        // of the window and return to the starting order.
+        for top in 0..self.names.len().saturating_sub(2) {
+            let swaps = [top, top + 1, top, top + 1, top, top + 1];
+            let mut best = (self.live_size(roots), 0);
+            for (step, &level) in swaps.iter().enumerate() {
+                self.swap_levels(level);
+                let size = self.live_size(roots);
+                if size < best.0 {
+                    best = (size, step + 1);
+                }
+            }
+            for &level in &swaps[..best.1] {
+                self.swap_levels(level);
+            }
+        }
+    }
+
+    /// Reorders the variables to shrink the diagrams reachable from `roots`.
+    ///
+    /// Handles stay valid and keep denoting the same functions.
+    pub fn reorder(&mut self, roots: &[Bdd], method: Reordering) -> ReorderReport {
+        let before = self.live_size(roots);
+        if self.names.len() > 1 {
+            match method {
+                Reordering::Sifting => self.sift(roots),
+                Reordering::Window3 => self.window3(roots),
+            }
+        }
+        ReorderReport {
+            before,
+            after: self.live_size(roots),
+            order: self.order().into_iter().map(String::from).collect(),
+        }
+    }
+
+    /// Sifts automatically once the diagrams built by [`crate::Formula::to_bdd`]
+    /// exceed `threshold` nodes.
+    ///
+    /// The threshold doubles after every automatic pass so that reordering
+    /// does not run again until the diagrams have grown substantially.
+    pub fn set_auto_reorder(&mut self, threshold: Option<usize>) {
+        self.reorder_threshold = threshold;
+    }
+
+    /// Reports of the automatic reordering passes run so far.
+    pub fn reorder_reports(&self) -> &[ReorderReport] {
+        &self.reorder_reports
+    }
+
+    /// Runs automatic reordering if the built diagrams exceed the threshold.
+    pub(crate) fn maybe_reorder(&mut self) {
+        let Some(threshold) = self.reorder_threshold else {
+            return;
+        };
+        let roots = self.roots.clone();
+        if self.live_size(&roots) > threshold {
+            let report = self.reorder(&roots, Reordering::Sifting);
+            self.reorder_threshold = Some(threshold.max(report.after) * 2);
+            self.reorder_reports.push(report);
+        }
+    }
+}
@@ -0,0 +1,225 @@
+use std::{
+    collections::HashMap,
+    ops::{BitAnd, BitOr, BitXor, Not},
+};
+
+use crate::{Formula, Node, Visitor};
+
+/// An instruction of a compiled formula, run on a stack of values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Op {
+    Var(u32),
+    Const(bool),
+    Not,
+    And,
+    Or,
+    If,
+    Iff,
+}
+
+/// A value the program can run on: a single `bool`, or a word of rows
+/// evaluated side by side.
+pub(crate) trait Word:
+    Copy + Not<Output = Self> + BitAnd<Output = Self> + BitOr<Output = Self> + BitXor<Output = Self>
+{
+    fn splat(value: bool) -> Self;
+}
+
+impl Word for bool {
+    fn splat(value: bool) -> bool {
+        value
+    }
+}
+
+impl Word for u64 {
+    fn splat(value: bool) -> u64 {
+        if value {
+            u64::MAX
+        } else {
+            0
+        }
+    }
+}
+
+/// A formula with its atoms resolved to dense indices, for fast repeated
+/// evaluation.
+///
+/// The tree is flattened into postfix order once, so evaluating it needs
+/// neither string lookups nor recursion.
+#[derive(Clone, Debug)]
+pub struct CompiledFormula {
+    variables: Vec<String>,
+    program: Vec<Op>,
+    /// Deepest the value stack gets while running the program.
+    depth: usize,
+}
+
+/// Emits the instructions of every node after those of its operands, so the
+/// program is in postfix order.
+struct Compiler<F> {
+    index: F,
+    program: Vec<Op>,
+    /// Set once an atom has no index.
+    missing: bool,
+}
+
+impl<'a, F: Fn(&str) -> Option<u32>> Visitor<'a> for Compiler<F> {
+    fn pre(&mut self, _: &'a Node) -> bool {
+        !self.missing
+    }
+
+    fn post(&mut self, node: &'a Node) {
+        let op = match node {
+            Node::Atom(name) => match (self.index)(name) {
+                Some(i) => Op::Var(i),
+                None => {
+                    self.missing = true;
+                    return;
+                }
+            },
+            Node::Value(b) => Op::Const(*b),
+            Node::Not(_) => Op::Not,
+            Node::And(_, _) => Op::And,
+            Node::Or(_, _) => Op::Or,
+            Node::If(_, _) => Op::If,
+            Node::Iff(_, _) => Op::Iff,
+        };
+        self.program.push(op);
+    }
+}
+
+impl CompiledFormula {
+    /// Compiles `formula` over `variables`, which must include all of its
+    /// variables. Returns `None` if one is missing.
+    pub fn new(formula: &Formula, variables: &[&str]) -> Option<CompiledFormula> {
+        let indices: HashMap<&str, u32> = variables
+            .iter()
+            .enumerate()
+            .map(|(i, &v)| (v, i as u32))
+            .collect();
+        let variables = variables.iter().map(|v| v.to_string()).collect();
+        CompiledFormula::compile(formula, |name| indices.get(name).copied(), variables)
+    }
+
+    /// Compiles `formula` with the atom `name` read from variable
+    /// `index(name)`, or returns `None` if that is `None` for some atom.
+    pub(crate) fn compile(
+        formula: &Formula,
+        index: impl Fn(&str) -> Option<u32>,
+        variables: Vec<String>,
+    ) -> Option<CompiledFormula> {
+        let mut compiler = Compiler {
+            index,
+            program: Vec::new(),
+            missing: false,
+        };
+        formula.root.walk(&mut compiler);
+        if compiler.missing {
+            return None;
+        }
+        let program = compiler.program;
+
+        let mut height = 0usize;
+        let mut depth = 0;
+        for op in &program {
+            match op {
+                Op::Var(_) | Op::Const(_) => height += 1,
+                Op::Not => {}
+                _ => height -= 1,
+            }
+            depth = depth.max(height);
+        }
+        Some(CompiledFormula {
+            variables,
+            program,
+            depth,
+        })
+    }
+
+    /// The variables in index order.
+    pub fn variables(&self) -> &[String] {
+        &self.variables
+    }
+
+    /// Index of the variable `name`, if it is one of the variables.
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.variables.iter().position(|v| v == name)
+    }
+
+    /// Runs the program, reading variable `i` with `var(i)`. `stack` must
+    /// hold at least `self.depth` values.
+    pub(crate) fn run<W: Word>(&self, var: impl Fn(usize) -> W, stack: &mut [W]) -> W {
+        let mut height = 0;
+        for op in &self.program {
+            match *op {
+                Op::Var(i) => {
+                    stack[height] = var(i as usize);
+                    height += 1;
+                }
+                Op::Const(b) => {
+                    stack[height] = W::splat(b);
+                    height += 1;
+                }
+                Op::Not => stack[height - 1] = !stack[height - 1],
+                op => {
+                    height -= 1;
+                    let (left, right) = (stack[height - 1], stack[height]);
+                    stack[height - 1] = match op {
+                        Op::And => left & right,
+                        Op::Or => left | right,
+                        Op::If => !left | right,
+                        _ => !(left ^ right),
+                    };
+                }
+            }
+        }
+        stack[0]
+    }
+
+    /// Runs the program on a stack on the call stack when it is shallow
+    /// enough, avoiding an allocation per evaluation.
+    fn run_bool(&self, var: impl Fn(usize) -> bool) -> bool {
+        const INLINE: usize = 32;
+        if self.depth <= INLINE {
+            self.run(var, &mut [false; INLINE])
+        } else {
+            self.run(var, &mut vec![false; self.depth])
+        }
+    }
+
+    /// Evaluates the formula where variable `i` has the value `values[i]`.
+    pub fn eval(&self, values: &[bool]) -> bool {
+        self.run_bool(|i| values[i])
+    }
+
+    /// Evaluates row `row` of the truth table over the variables, numbered as
+    /// in [`Formula::truth_vector`]: variable `i` of `n` is bit `n - 1 - i`.
+    pub fn eval_bits(&self, row: u64) -> bool {
+        let n = self.variables.len();
+        self.run_bool(|i| row >> (n - 1 - i) & 1 == 1)
+    }
+
+    /// Evaluates every row, as in [`Formula::truth_vector`].
+    pub fn truth_vector(&self) -> Vec<bool> {
+        self.truth_bits().iter().collect()
+    }
+
+    /// Deepest the value stack gets while running the program.
+    pub(crate) fn depth(&self) -> usize {
+        self.depth
+    }
+}
+
+impl Formula {
+    /// Compiles the formula over its [sorted
+    /// variables](Formula::sorted_variables).
+    pub fn compile(&self) -> CompiledFormula {
+        self.compile_over(&self.sorted_variables())
+    }
+
+    /// Compiles the formula over `variables`, which must include all of the
+    /// formula's variables.
+    pub(crate) fn compile_over(&self, variables: &[&str]) -> CompiledFormula {
+        CompiledFormula::new(self, variables).expect("variables must cover the formula")
+    }
+}
@@ -0,0 +1,81 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{Fold, Formula, Node};
+
+/// Replaces the atoms found in `map` by their formulas.
+struct Substitution<'m> {
+    map: &'m HashMap<String, Formula>,
+}
+
+impl Fold for Substitution<'_> {
+    fn fold_atom(&mut self, name: String) -> Node {
+        match self.map.get(&name) {
+            Some(formula) => formula.root.clone(),
+            None => Node::Atom(name),
+        }
+    }
+}
+
+/// Joins `formulas` pairwise, level by level, so the tree has logarithmic
+/// depth. Returns `Node::Value(and)` if there are none.
+fn balanced(formulas: impl IntoIterator<Item = Formula>, and: bool) -> Formula {
+    let mut variables = HashSet::new();
+    let mut level: Vec<Node> = formulas
+        .into_iter()
+        .map(|formula| {
+            variables.extend(formula.variables);
+            formula.root
+        })
+        .collect();
+    if level.is_empty() {
+        return Formula::from_root(Node::Value(and));
+    }
+    while level.len() > 1 {
+        let mut nodes = level.into_iter();
+        let mut next = Vec::with_capacity(nodes.len() / 2 + 1);
+        while let Some(left) = nodes.next() {
+            next.push(match nodes.next() {
+                Some(right) if and => Node::and(left, right),
+                Some(right) => Node::or(left, right),
+                None => left,
+            });
+        }
+        level = next;
+    }
+    Formula {
+        root: level.pop().unwrap(),
+        variables,
+    }
+}
+
+impl Formula {
+    /// Replaces every atom named in `map` by its formula, all at once: atoms
+    /// inside the inserted formulas are not substituted again.
+    pub fn substitute(&self, map: &HashMap<String, Formula>) -> Formula {
+        let root = self.root.clone().fold(&mut Substitution { map });
+        Formula::from_root(root)
+    }
+
+    /// Renames variables simultaneously, so e.g. `a` and `b` can be swapped.
+    pub fn rename(&self, names: &HashMap<String, String>) -> Formula {
+        let map = names
+            .iter()
+            .map(|(from, to)| (from.clone(), Formula::from_root(Node::Atom(to.clone()))))
+            .collect();
+        self.substitute(&map)
+    }
+
+    /// The conjunction of `formulas`, or `T` if there are none.
+    ///
+    /// The conjunction is built as a balanced tree, so even millions of
+    /// operands nest only a few dozen levels deep.
+    pub fn and_all(formulas: impl IntoIterator<Item = Formula>) -> Formula {
+        balanced(formulas, true)
+    }
+
+    /// The disjunction of `formulas`, or `F` if there are none, built like
+    /// [`Formula::and_all`].
+    pub fn or_all(formulas: impl IntoIterator<Item = Formula>) -> Formula {
+        balanced(formulas, false)
+    }
+}
@@ -0,0 +1,54 @@
+use crate::{Dnf, Literal};
+
+/// A product term over `n` variables, as used by the two-level minimizers.
+///
+/// Variable `j` corresponds to bit `n - 1 - j`, matching the minterm numbering
+/// of [`crate::Formula::truth_vector`]. A set bit in `care` means the variable
+/// appears in the term, with the polarity given by the same bit of `bits`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub(crate) struct Cube {
+    pub bits: u64,
+    pub care: u64,
+}
+
+impl Cube {
+    pub fn minterm(index: u64, num_vars: usize) -> Cube {
+        Cube {
+            bits: index,
+            care: mask(num_vars),
+        }
+    }
+
+    pub fn contains(self, minterm: u64) -> bool {
+        minterm & self.care == self.bits
+    }
+
+    pub fn literals(self) -> u32 {
+        self.care.count_ones()
+    }
+
+    pub fn to_literals(self, variables: &[&str]) -> Vec<Literal> {
+        let n = variables.len();
+        variables
+            .iter()
+            .enumerate()
+            .filter(|&(j, _)| self.care >> (n - 1 - j) & 1 == 1)
+            .map(|(j, var)| Literal::new(*var, self.bits >> (n - 1 - j) & 1 == 1))
+            .collect()
+    }
+}
+
+pub(crate) fn mask(num_vars: usize) -> u64 {
+    if num_vars >= 64 {
+        u64::MAX
+    } else {
+        (1 << num_vars) - 1
+    }
+}
+
+/// Builds the sum of products of `cubes`.
+pub(crate) fn to_dnf(cubes: &[Cube], variables: &[&str]) -> Dnf {
+    Dnf {
+        terms: cubes.iter().map(|c| c.to_literals(variables)).collect(),
+    }
+}
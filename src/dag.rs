@@ -0,0 +1,247 @@
+use std::collections::HashMap;
+
+use crate::{Formula, Node, Visitor};
+
+/// Handle to a node owned by a [`Dag`].
+///
+/// Nodes are hash-consed, so two handles from the same arena are equal
+/// exactly when they denote structurally identical subformulas.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(u32);
+
+impl NodeId {
+    fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// A node of a [`Dag`], with its operands given by handle.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DagNode {
+    And(NodeId, NodeId),
+    Or(NodeId, NodeId),
+    Not(NodeId),
+    If(NodeId, NodeId),
+    Iff(NodeId, NodeId),
+    Atom(String),
+    Value(bool),
+}
+
+impl DagNode {
+    fn operands(&self) -> (Option<NodeId>, Option<NodeId>) {
+        match *self {
+            DagNode::And(l, r) | DagNode::Or(l, r) | DagNode::If(l, r) | DagNode::Iff(l, r) => {
+                (Some(l), Some(r))
+            }
+            DagNode::Not(operand) => (Some(operand), None),
+            DagNode::Atom(_) | DagNode::Value(_) => (None, None),
+        }
+    }
+}
+
+/// An arena of formulas stored as a directed acyclic graph.
+///
+/// Every distinct subformula is stored once: adding a node that already exists
+/// returns the existing handle. Operands are always added before the nodes
+/// using them, so handles are in topological order.
+#[derive(Clone, Debug, Default)]
+pub struct Dag {
+    nodes: Vec<DagNode>,
+    unique: HashMap<DagNode, NodeId>,
+}
+
+/// Adds every node of a tree after its operands.
+struct Adder<'d> {
+    dag: &'d mut Dag,
+    ids: Vec<NodeId>,
+}
+
+impl<'a> Visitor<'a> for Adder<'_> {
+    fn post(&mut self, node: &'a Node) {
+        let mut operand = || self.ids.pop().unwrap();
+        let dag_node = match node {
+            Node::And(_, _) => {
+                let right = operand();
+                DagNode::And(operand(), right)
+            }
+            Node::Or(_, _) => {
+                let right = operand();
+                DagNode::Or(operand(), right)
+            }
+            Node::If(_, _) => {
+                let right = operand();
+                DagNode::If(operand(), right)
+            }
+            Node::Iff(_, _) => {
+                let right = operand();
+                DagNode::Iff(operand(), right)
+            }
+            Node::Not(_) => DagNode::Not(operand()),
+            Node::Atom(name) => DagNode::Atom(name.clone()),
+            Node::Value(b) => DagNode::Value(*b),
+        };
+        let id = self.dag.add(dag_node);
+        self.ids.push(id);
+    }
+}
+
+impl Dag {
+    pub fn new() -> Dag {
+        Dag::default()
+    }
+
+    /// The number of distinct nodes in the arena.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn node(&self, id: NodeId) -> &DagNode {
+        &self.nodes[id.index()]
+    }
+
+    /// Returns the handle of `node`, adding it if it is new.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an operand is not a handle from this arena.
+    pub fn add(&mut self, node: DagNode) -> NodeId {
+        if let Some(&id) = self.unique.get(&node) {
+            return id;
+        }
+        let (left, right) = node.operands();
+        for operand in left.into_iter().chain(right) {
+            assert!(operand.index() < self.nodes.len(), "unknown operand");
+        }
+        let id = NodeId(self.nodes.len() as u32);
+        self.nodes.push(node.clone());
+        self.unique.insert(node, id);
+        id
+    }
+
+    /// Adds the tree `root`, sharing every repeated subformula.
+    pub fn add_node(&mut self, root: &Node) -> NodeId {
+        let mut adder = Adder {
+            dag: self,
+            ids: Vec::new(),
+        };
+        root.walk(&mut adder);
+        adder.ids.pop().unwrap()
+    }
+
+    /// Expands `root` back into a tree. Shared subformulas are copied at
+    /// every use, so the tree can be exponentially larger than the graph.
+    pub fn to_node(&self, root: NodeId) -> Node {
+        let reachable = self.reachable(&[root]);
+        let mut uses = vec![0usize; self.nodes.len()];
+        for &id in &reachable {
+            let (left, right) = self.node(id).operands();
+            for operand in left.into_iter().chain(right) {
+                uses[operand.index()] += 1;
+            }
+        }
+        // Operands come before their users, so their trees are ready. The
+        // last use of a tree takes it instead of copying it.
+        let mut trees: Vec<Option<Node>> = vec![None; self.nodes.len()];
+        for id in reachable {
+            let mut operand = |operand: NodeId| {
+                let i = operand.index();
+                uses[i] -= 1;
+                if uses[i] == 0 {
+                    trees[i].take()
+                } else {
+                    trees[i].clone()
+                }
+                .unwrap()
+            };
+            let tree = match *self.node(id) {
+                DagNode::And(l, r) => Node::and(operand(l), operand(r)),
+                DagNode::Or(l, r) => Node::or(operand(l), operand(r)),
+                DagNode::If(l, r) => Node::implies(operand(l), operand(r)),
+                DagNode::Iff(l, r) => Node::iff(operand(l), operand(r)),
+                DagNode::Not(x) => Node::not(operand(x)),
+                DagNode::Atom(ref name) => Node::Atom(name.clone()),
+                DagNode::Value(b) => Node::Value(b),
+            };
+            trees[id.index()] = Some(tree);
+        }
+        trees[root.index()].take().unwrap()
+    }
+
+    pub fn to_formula(&self, root: NodeId) -> Formula {
+        Formula::from_root(self.to_node(root))
+    }
+
+    /// Handles of the nodes reachable from `roots`, in increasing order.
+    fn reachable(&self, roots: &[NodeId]) -> Vec<NodeId> {
+        let mut seen = vec![false; self.nodes.len()];
+        let mut pending = roots.to_vec();
+        while let Some(id) = pending.pop() {
+            if std::mem::replace(&mut seen[id.index()], true) {
+                continue;
+            }
+            let (left, right) = self.node(id).operands();
+            pending.extend(left.into_iter().chain(right));
+        }
+        (0..self.nodes.len())
+            .filter(|&i| seen[i])
+            .map(|i| NodeId(i as u32))
+            .collect()
+    }
+
+    /// The number of distinct nodes reachable from `root`.
+    pub fn size(&self, root: NodeId) -> usize {
+        self.reachable(&[root]).len()
+    }
+
+    /// Evaluates every root, visiting each shared node once.
+    ///
+    /// As with [`Formula::eval`], a result is `None` if it depends on a
+    /// variable missing from `vars`.
+    pub fn eval_many(&self, roots: &[NodeId], vars: &HashMap<String, bool>) -> Vec<Option<bool>> {
+        let mut values: Vec<Option<bool>> = vec![None; self.nodes.len()];
+        // Operands come before their users, so one pass in order suffices
+        for id in self.reachable(roots) {
+            let value = |id: NodeId| values[id.index()];
+            let value = match *self.node(id) {
+                DagNode::And(l, r) => match value(l) {
+                    Some(true) => value(r),
+                    decided => decided,
+                },
+                DagNode::Or(l, r) => match value(l) {
+                    Some(false) => value(r),
+                    decided => decided,
+                },
+                DagNode::If(l, r) => match value(l) {
+                    Some(true) => value(r),
+                    Some(false) => Some(true),
+                    None => None,
+                },
+                DagNode::Iff(l, r) => match (value(l), value(r)) {
+                    (Some(a), Some(b)) => Some(a == b),
+                    _ => None,
+                },
+                DagNode::Not(operand) => value(operand).map(|b| !b),
+                DagNode::Atom(ref name) => vars.get(name).copied(),
+                DagNode::Value(b) => Some(b),
+            };
+            values[id.index()] = value;
+        }
+        roots.iter().map(|id| values[id.index()]).collect()
+    }
+
+    pub fn eval(&self, root: NodeId, vars: &HashMap<String, bool>) -> Option<bool> {
+        self.eval_many(&[root], vars)[0]
+    }
+}
+
+impl Formula {
+    /// Adds the formula to `dag`, sharing subformulas with everything already
+    /// in it.
+    pub fn to_dag(&self, dag: &mut Dag) -> NodeId {
+        dag.add_node(&self.root)
+    }
+}
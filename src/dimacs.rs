@@ -0,0 +1,252 @@
+use std::{collections::HashMap, fmt, fmt::Write};
+
+use crate::{Cnf, Formula, Literal, Tseitin};
+
+/// Errors found while reading a DIMACS CNF file.
+///
+/// Line numbers are 1-based.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DimacsError {
+    /// No `p cnf <variables> <clauses>` line before the first clause.
+    MissingHeader,
+    /// The problem line is not of the form `p cnf <variables> <clauses>`.
+    InvalidHeader { line: usize, reason: String },
+    /// A second problem line was found.
+    DuplicateHeader { line: usize },
+    /// A token in a clause is not an integer.
+    InvalidLiteral { line: usize, token: String },
+    /// A literal refers to a variable above the declared count.
+    VariableOutOfRange {
+        line: usize,
+        variable: u64,
+        declared: u64,
+    },
+    /// The number of clauses differs from the declared count.
+    ClauseCountMismatch { declared: usize, found: usize },
+    /// A name was given to two variables, or to one variable while another
+    /// is already known by it.
+    DuplicateName { line: usize, name: String },
+}
+
+impl fmt::Display for DimacsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DimacsError::MissingHeader => write!(f, "missing `p cnf` problem line"),
+            DimacsError::InvalidHeader { line, reason } => {
+                write!(f, "line {}: invalid problem line: {}", line, reason)
+            }
+            DimacsError::DuplicateHeader { line } => {
+                write!(f, "line {}: duplicate problem line", line)
+            }
+            DimacsError::InvalidLiteral { line, token } => {
+                write!(f, "line {}: invalid literal `{}`", line, token)
+            }
+            DimacsError::VariableOutOfRange {
+                line,
+                variable,
+                declared,
+            } => write!(
+                f,
+                "line {}: variable {} exceeds the declared {} variables",
+                line, variable, declared
+            ),
+            DimacsError::ClauseCountMismatch { declared, found } => write!(
+                f,
+                "expected {} clauses as declared, found {}",
+                declared, found
+            ),
+            DimacsError::DuplicateName { line, name } => {
+                write!(
+                    f,
+                    "line {}: variable name `{}` is already in use",
+                    line, name
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for DimacsError {}
+
+fn parse_header(line: usize, text: &str) -> Result<(u64, usize), DimacsError> {
+    let invalid = |reason: String| DimacsError::InvalidHeader { line, reason };
+    let mut fields = text.split_whitespace().skip(1);
+    match fields.next() {
+        Some("cnf") => {}
+        Some(format) => return Err(invalid(format!("unsupported format `{}`", format))),
+        None => return Err(invalid("missing format".to_string())),
+    }
+    let mut count = |what: &str| {
+        let field = fields
+            .next()
+            .ok_or_else(|| invalid(format!("missing {} count", what)))?;
+        field
+            .parse::<u64>()
+            .map_err(|_| invalid(format!("invalid {} count `{}`", what, field)))
+    };
+    let variables = count("variable")?;
+    let clauses = count("clause")? as usize;
+    if let Some(extra) = fields.next() {
+        return Err(invalid(format!("unexpected `{}`", extra)));
+    }
+    Ok((variables, clauses))
+}
+
+impl Cnf {
+    /// Reads a CNF in DIMACS format.
+    ///
+    /// Comments of the form `c var <number> <name>`, as written by
+    /// [`Cnf::to_dimacs`], name the corresponding variable; other variables
+    /// are named `x<number>`, and other comments are ignored. A `%` line ends
+    /// the clause list.
+    ///
+    /// Two variables never share a name: a name given to two variables, or
+    /// to one while another variable is already called that, is an error.
+    pub fn from_dimacs(source: &str) -> Result<Cnf, DimacsError> {
+        let mut names: HashMap<u64, String> = HashMap::new();
+        // The variable each name was given to
+        let mut numbers: HashMap<String, u64> = HashMap::new();
+        let mut header = None;
+        let mut clauses = Vec::new();
+        let mut clause = Vec::new();
+
+        for (i, text) in source.lines().enumerate() {
+            let line = i + 1;
+            let text = text.trim();
+            if text.is_empty() {
+                continue;
+            }
+            if let Some(comment) = text.strip_prefix('c') {
+                let mut fields = comment.split_whitespace();
+                if let (Some("var"), Some(number), Some(name), None) =
+                    (fields.next(), fields.next(), fields.next(), fields.next())
+                {
+                    if let Ok(number) = number.parse() {
+                        let name = name.to_string();
+                        if *numbers.entry(name.clone()).or_insert(number) != number {
+                            return Err(DimacsError::DuplicateName { line, name });
+                        }
+                        names.insert(number, name);
+                    }
+                }
+                continue;
+            }
+            if text.starts_with('p') {
+                if header.is_some() {
+                    return Err(DimacsError::DuplicateHeader { line });
+                }
+                header = Some(parse_header(line, text)?);
+                continue;
+            }
+            if text.starts_with('%') {
+                break;
+            }
+            let Some((declared, _)) = header else {
+                return Err(DimacsError::MissingHeader);
+            };
+            for token in text.split_whitespace() {
+                let literal: i64 = token.parse().map_err(|_| DimacsError::InvalidLiteral {
+                    line,
+                    token: token.to_string(),
+                })?;
+                if literal == 0 {
+                    clauses.push(std::mem::take(&mut clause));
+                    continue;
+                }
+                let variable = literal.unsigned_abs();
+                if variable > declared {
+                    return Err(DimacsError::VariableOutOfRange {
+                        line,
+                        variable,
+                        declared,
+                    });
+                }
+                let name = match names.get(&variable) {
+                    Some(name) => name.clone(),
+                    None => {
+                        let name = format!("x{}", variable);
+                        if numbers.get(&name).is_some_and(|&n| n != variable) {
+                            return Err(DimacsError::DuplicateName { line, name });
+                        }
+                        name
+                    }
+                };
+                clause.push(Literal::new(name, literal > 0));
+            }
+        }
+
+        let Some((_, declared)) = header else {
+            return Err(DimacsError::MissingHeader);
+        };
+        // Tolerate a missing terminating 0 on the last clause
+        if !clause.is_empty() {
+            clauses.push(clause);
+        }
+        if clauses.len() != declared {
+            return Err(DimacsError::ClauseCountMismatch {
+                declared,
+                found: clauses.len(),
+            });
+        }
+        Ok(Cnf { clauses })
+    }
+
+    /// Writes the CNF in DIMACS format.
+    ///
+    /// Variables are numbered by first occurrence, and a
+    /// `c var <number> <name>` comment is emitted for each.
+    pub fn to_dimacs(&self) -> String {
+        write_dimacs(self, &[])
+    }
+}
+
+/// Writes `cnf` numbering the variables in `order` first.
+fn write_dimacs(cnf: &Cnf, order: &[&str]) -> String {
+    let mut numbers: HashMap<&str, usize> = HashMap::new();
+    let mut names = Vec::new();
+    let all = order
+        .iter()
+        .copied()
+        .chain(cnf.clauses.iter().flatten().map(|l| l.name.as_str()));
+    for name in all {
+        numbers.entry(name).or_insert_with(|| {
+            names.push(name);
+            names.len()
+        });
+    }
+
+    let mut out = String::new();
+    for (i, name) in names.iter().enumerate() {
+        writeln!(out, "c var {} {}", i + 1, name).unwrap();
+    }
+    writeln!(out, "p cnf {} {}", names.len(), cnf.clauses.len()).unwrap();
+    for clause in &cnf.clauses {
+        for literal in clause {
+            let number = numbers[literal.name.as_str()];
+            let sign = if literal.positive { "" } else { "-" };
+            write!(out, "{}{} ", sign, number).unwrap();
+        }
+        writeln!(out, "0").unwrap();
+    }
+    out
+}
+
+impl Tseitin {
+    /// Writes the encoding in DIMACS format.
+    ///
+    /// The original variables are numbered first, in truth table order, so a
+    /// model printed by an external solver reads directly as an assignment of
+    /// the formula.
+    pub fn to_dimacs(&self) -> String {
+        let mut variables: Vec<_> = self.variables.iter().map(String::as_str).collect();
+        variables.sort_unstable();
+        write_dimacs(&self.cnf, &variables)
+    }
+}
+
+impl Formula {
+    /// Reads a formula from a CNF in DIMACS format.
+    pub fn from_dimacs(source: &str) -> Result<Formula, DimacsError> {
+        Ok(Cnf::from_dimacs(source)?.to_formula())
+    }
+}
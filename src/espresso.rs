@@ -0,0 +1,330 @@
+use std::fmt;
+
+use crate::{
+    cube::{self, Cube},
+    truth_vector, Bdd, BddManager, BddOp, Formula, Node,
+};
+
+fn intersects(a: Cube, b: Cube) -> bool {
+    (a.bits ^ b.bits) & a.care & b.care == 0
+}
+
+fn covers(a: Cube, b: Cube) -> bool {
+    a.care & b.care == a.care && b.bits & a.care == a.bits
+}
+
+/// Restricts `cover` to the subspace of `cube`, dropping the cube's variables.
+fn cofactor(cover: &[Cube], cube: Cube) -> Vec<Cube> {
+    cover
+        .iter()
+        .filter(|&&c| intersects(c, cube))
+        .map(|&c| Cube {
+            bits: c.bits & !cube.care,
+            care: c.care & !cube.care,
+        })
+        .collect()
+}
+
+/// Whether `cover` contains every minterm, by unate recursive splitting.
+fn tautology(cover: &[Cube]) -> bool {
+    if cover.iter().any(|c| c.care == 0) {
+        return true;
+    }
+    // Split on the variable that appears most often in both polarities
+    let all_care = cover.iter().fold(0, |acc, c| acc | c.care);
+    let mut best = None;
+    let mut remaining = all_care;
+    while remaining != 0 {
+        let bit = remaining & remaining.wrapping_neg();
+        remaining &= remaining - 1;
+        let ones = cover.iter().filter(|c| c.care & c.bits & bit != 0).count();
+        let zeros = cover.iter().filter(|c| c.care & !c.bits & bit != 0).count();
+        if ones > 0 && zeros > 0 && !matches!(best, Some((_, n)) if n >= ones + zeros) {
+            best = Some((bit, ones + zeros));
+        }
+    }
+    // A unate cover is a tautology only if it holds the universal cube
+    let Some((bit, _)) = best else {
+        return false;
+    };
+    [0, bit].iter().all(|&value| {
+        tautology(&cofactor(
+            cover,
+            Cube {
+                bits: value,
+                care: bit,
+            },
+        ))
+    })
+}
+
+/// Whether `cube` is contained in the union of `cover`.
+fn contained(cube: Cube, cover: &[Cube]) -> bool {
+    tautology(&cofactor(cover, cube))
+}
+
+fn cost(cover: &[Cube]) -> (usize, u32) {
+    (cover.len(), cover.iter().map(|c| c.literals()).sum())
+}
+
+/// Heuristic two-level minimizer over cube lists in the style of Espresso.
+struct Espresso {
+    off: Vec<Cube>,
+    dc: Vec<Cube>,
+}
+
+impl Espresso {
+    /// Raises each cube to a prime by dropping literals while it stays clear
+    /// of the off-set, then removes the cubes the result covers.
+    fn expand(&self, cover: Vec<Cube>) -> Vec<Cube> {
+        let mut cover = cover;
+        // Expanding the largest cubes first lets them swallow smaller ones
+        cover.sort_unstable_by_key(|c| (c.literals(), c.bits));
+        let mut expanded: Vec<Cube> = Vec::with_capacity(cover.len());
+        for mut cube in cover {
+            if expanded.iter().any(|&e| covers(e, cube)) {
+                continue;
+            }
+            let mut care = cube.care;
+            while care != 0 {
+                let bit = care & care.wrapping_neg();
+                care &= care - 1;
+                let raised = Cube {
+                    bits: cube.bits & !bit,
+                    care: cube.care & !bit,
+                };
+                if !self.off.iter().any(|&o| intersects(o, raised)) {
+                    cube = raised;
+                }
+            }
+            expanded.retain(|&e| !covers(cube, e));
+            expanded.push(cube);
+        }
+        expanded
+    }
+
+    /// Drops cubes covered by the rest of the cover and the don't-cares,
+    /// trying the smallest cubes first.
+    fn irredundant(&self, mut cover: Vec<Cube>) -> Vec<Cube> {
+        cover.sort_unstable_by_key(|c| std::cmp::Reverse((c.literals(), c.bits)));
+        let mut i = 0;
+        while i < cover.len() {
+            let cube = cover[i];
+            let rest: Vec<Cube> = cover
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| j != i)
+                .map(|(_, &c)| c)
+                .chain(self.dc.iter().copied())
+                .collect();
+            if contained(cube, &rest) {
+                cover.remove(i);
+            } else {
+                i += 1;
+            }
+        }
+        cover
+    }
+
+    /// Shrinks each cube to the part not covered by the rest of the cover,
+    /// giving the next expansion room to find different primes.
+    fn reduce(&self, mut cover: Vec<Cube>, num_vars: usize) -> Vec<Cube> {
+        for i in 0..cover.len() {
+            let rest: Vec<Cube> = cover
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| j != i)
+                .map(|(_, &c)| c)
+                .chain(self.dc.iter().copied())
+                .collect();
+            let mut cube = cover[i];
+            let free = cube::mask(num_vars) & !cube.care;
+            let mut remaining = free;
+            while remaining != 0 {
+                let bit = remaining & remaining.wrapping_neg();
+                remaining &= remaining - 1;
+                for value in [0, bit] {
+                    // Keep the half with `bit = value` if the other half is
+                    // already covered elsewhere
+                    let other = Cube {
+                        bits: cube.bits | (bit & !value),
+                        care: cube.care | bit,
+                    };
+                    if contained(other, &rest) {
+                        cube = Cube {
+                            bits: cube.bits | value,
+                            care: cube.care | bit,
+                        };
+                        break;
+                    }
+                }
+            }
+            cover[i] = cube;
+        }
+        cover
+    }
+
+    fn minimize(&self, on: Vec<Cube>, num_vars: usize) -> Vec<Cube> {
+        let mut cover = self.irredundant(self.expand(on));
+        loop {
+            let reduced = self.reduce(cover.clone(), num_vars);
+            let next = self.irredundant(self.expand(reduced));
+            if cost(&next) >= cost(&cover) {
+                return cover;
+            }
+            cover = next;
+        }
+    }
+}
+
+/// Converts the paths of `f` to cubes over the manager's variable order.
+fn bdd_cubes(manager: &BddManager, f: Bdd) -> Vec<Cube> {
+    let order = manager.order();
+    let n = order.len();
+    manager
+        .cubes(f)
+        .into_iter()
+        .map(|path| {
+            let mut cube = Cube { bits: 0, care: 0 };
+            for (j, var) in order.iter().enumerate() {
+                if let Some(&value) = path.get(*var) {
+                    let bit = 1 << (n - 1 - j);
+                    cube.care |= bit;
+                    if value {
+                        cube.bits |= bit;
+                    }
+                }
+            }
+            cube
+        })
+        .collect()
+}
+
+/// Largest number of variables [`Formula::minimize_auto`] minimizes exactly.
+const EXACT_MINIMIZE_LIMIT: usize = 6;
+
+/// Errors of the heuristic minimizer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MinimizeError {
+    /// The formula and the don't-cares have more than 64 variables together.
+    TooManyVariables(usize),
+    /// A verified minimization differs from the original formula on
+    /// `assignment`, the first one outside the don't-cares where they
+    /// disagree.
+    Mismatch { assignment: Vec<(String, bool)> },
+}
+
+impl fmt::Display for MinimizeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MinimizeError::TooManyVariables(count) => write!(
+                f,
+                "the heuristic minimizer supports at most 64 variables, found {}",
+                count
+            ),
+            MinimizeError::Mismatch { assignment } => {
+                write!(f, "minimized formula differs from the original on")?;
+                for (name, value) in assignment {
+                    write!(f, " {}{}", if *value { "" } else { "¬" }, name)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for MinimizeError {}
+
+impl Formula {
+    /// Returns a small, though not necessarily minimal, sum of products
+    /// equivalent to the formula, using the Espresso heuristics.
+    ///
+    /// The on-set and off-set are taken from the paths of the formula's BDD,
+    /// then the cover is improved by repeated reduce, expand and irredundant
+    /// passes until its cost stops decreasing. Unlike [`Formula::minimize`],
+    /// this never enumerates the truth table, so it handles functions of
+    /// dozens of variables. Functions of more than 64 variables are rejected
+    /// with [`MinimizeError::TooManyVariables`].
+    ///
+    /// If `verify` is set, every row of the truth table of the result is
+    /// checked against the original, and the first mismatch is returned as
+    /// [`MinimizeError::Mismatch`]. This is exponential in the number of
+    /// variables, and panics past [`MAX_TABLE_VARIABLES`](crate::MAX_TABLE_VARIABLES).
+    pub fn minimize_heuristic(&self, verify: bool) -> Result<Formula, MinimizeError> {
+        self.minimize_heuristic_with_dont_cares(&Formula::from_root(Node::Value(false)), verify)
+    }
+
+    /// Like [`Formula::minimize_heuristic`], but the result may take any value
+    /// on the rows where `dont_care` holds. Verification skips those rows.
+    pub fn minimize_heuristic_with_dont_cares(
+        &self,
+        dont_care: &Formula,
+        verify: bool,
+    ) -> Result<Formula, MinimizeError> {
+        let variables = self.variables_with(dont_care);
+        // Cubes keep one bit per variable in a `u64`
+        if variables.len() > 64 {
+            return Err(MinimizeError::TooManyVariables(variables.len()));
+        }
+        let mut manager = BddManager::with_order(&variables);
+        let f = self.to_bdd(&mut manager);
+        let dc = dont_care.to_bdd(&mut manager);
+        let care = manager.not(dc);
+        let not_f = manager.not(f);
+        let on = manager.apply(BddOp::And, f, care);
+        let off = manager.apply(BddOp::And, not_f, care);
+
+        let espresso = Espresso {
+            off: bdd_cubes(&manager, off),
+            dc: bdd_cubes(&manager, dc),
+        };
+        let mut cover = espresso.minimize(bdd_cubes(&manager, on), variables.len());
+        cover.sort_unstable_by(|a, b| b.cmp(a));
+        let result = cube::to_dnf(&cover, &variables).to_formula();
+
+        if verify {
+            if let Some(assignment) = self.first_difference(&result, dont_care) {
+                return Err(MinimizeError::Mismatch { assignment });
+            }
+        }
+        Ok(result)
+    }
+
+    /// Minimizes with [`Formula::minimize_with_dont_cares`] if the formula
+    /// and the don't-cares have at most six variables together, and with
+    /// [`Formula::minimize_heuristic_with_dont_cares`] otherwise.
+    ///
+    /// Exact minimization is exponential, and random functions of as few as
+    /// eight variables already take far longer than the heuristic.
+    pub fn minimize_auto(&self, dont_care: &Formula) -> Result<Formula, MinimizeError> {
+        if self.variables_with(dont_care).len() <= EXACT_MINIMIZE_LIMIT {
+            Ok(self.minimize_with_dont_cares(dont_care))
+        } else {
+            self.minimize_heuristic_with_dont_cares(dont_care, false)
+        }
+    }
+
+    /// Returns the first assignment outside `dont_care` on which the two
+    /// formulas disagree.
+    fn first_difference(
+        &self,
+        other: &Formula,
+        dont_care: &Formula,
+    ) -> Option<Vec<(String, bool)>> {
+        let variables = self.variables_with(dont_care);
+        let num_vars = variables.len();
+        let dont_care = dont_care.compile_over(&variables).truth_bits();
+        let this = self.compile_over(&variables).truth_bits();
+        let other = other.compile_over(&variables).truth_bits();
+        (0..truth_vector::row_count(num_vars)).find_map(|i| {
+            let differs = !dont_care.get(i) && this.get(i) != other.get(i);
+            differs.then(|| {
+                variables
+                    .iter()
+                    .enumerate()
+                    .map(|(j, var)| (var.to_string(), (i >> (num_vars - 1 - j)) & 1 == 1))
+                    .collect()
+            })
+        })
+    }
+}
@@ -0,0 +1,360 @@
+use std::fmt::{self, Write};
+
+use crate::{
+    cube::{self, Cube},
+    qm, Formula, Node,
+};
+
+/// Value of a Karnaugh map cell.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KarnaughCell {
+    Zero,
+    One,
+    DontCare,
+}
+
+impl KarnaughCell {
+    fn symbol(self) -> &'static str {
+        match self {
+            KarnaughCell::Zero => "0",
+            KarnaughCell::One => "1",
+            KarnaughCell::DontCare => "X",
+        }
+    }
+}
+
+/// Returned when a formula has too few or too many variables for a map.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KarnaughMapError {
+    pub variables: usize,
+}
+
+impl fmt::Display for KarnaughMapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Karnaugh maps need 2 to 6 variables, found {}",
+            self.variables
+        )
+    }
+}
+
+impl std::error::Error for KarnaughMapError {}
+
+/// A Karnaugh map of a formula with 2 to 6 variables.
+///
+/// The first half of the variables in truth table order, rounded down, label
+/// the rows and the rest label the columns. Both axes are Gray coded, so
+/// adjacent cells differ in exactly one variable.
+#[derive(Clone, Debug)]
+pub struct KarnaughMap {
+    variables: Vec<String>,
+    row_vars: usize,
+    cells: Vec<Vec<KarnaughCell>>,
+    groups: Vec<Cube>,
+}
+
+fn gray(i: usize) -> usize {
+    i ^ (i >> 1)
+}
+
+fn bits(code: usize, width: usize) -> String {
+    (0..width)
+        .rev()
+        .map(|b| if code >> b & 1 == 1 { '1' } else { '0' })
+        .collect()
+}
+
+/// Label for the group at `index`: `A` to `Z`, then `AA`, `AB` and so on.
+fn group_label(index: usize) -> String {
+    let letter = |i: usize| (b'A' + i as u8) as char;
+    if index < 26 {
+        letter(index).to_string()
+    } else {
+        format!("{}{}", letter(index / 26 - 1), letter(index % 26))
+    }
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn latex_math(s: &str) -> String {
+    s.replace('¬', "\\lnot ")
+        .replace('∧', "\\land")
+        .replace('∨', "\\lor")
+        .replace('_', "\\_")
+}
+
+impl KarnaughMap {
+    fn col_vars(&self) -> usize {
+        self.variables.len() - self.row_vars
+    }
+
+    /// Minterm index of the cell at `row` and `col`, as in
+    /// [`Formula::truth_vector`].
+    pub fn minterm(&self, row: usize, col: usize) -> usize {
+        gray(row) << self.col_vars() | gray(col)
+    }
+
+    pub fn cell(&self, row: usize, col: usize) -> KarnaughCell {
+        self.cells[row][col]
+    }
+
+    pub fn rows(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cells[0].len()
+    }
+
+    /// Overlays the prime implicants selected by the Quine–McCluskey
+    /// minimizer, each labelled with a letter.
+    pub fn with_groups(mut self) -> KarnaughMap {
+        let mut on = Vec::new();
+        let mut on_or_dc = Vec::new();
+        for row in 0..self.rows() {
+            for col in 0..self.cols() {
+                let minterm = self.minterm(row, col) as u64;
+                match self.cell(row, col) {
+                    KarnaughCell::One => {
+                        on.push(minterm);
+                        on_or_dc.push(minterm);
+                    }
+                    KarnaughCell::DontCare => on_or_dc.push(minterm),
+                    KarnaughCell::Zero => {}
+                }
+            }
+        }
+        let primes = qm::prime_implicants(&on_or_dc, self.variables.len());
+        self.groups = qm::minimum_cover(&primes, &on);
+        self
+    }
+
+    /// The overlaid groups as `(label, product term)` pairs.
+    pub fn groups(&self) -> Vec<(String, String)> {
+        let variables: Vec<_> = self.variables.iter().map(String::as_str).collect();
+        self.groups
+            .iter()
+            .enumerate()
+            .map(|(i, &group)| {
+                let term = cube::to_dnf(&[group], &variables);
+                (group_label(i), term.to_string())
+            })
+            .collect()
+    }
+
+    /// Labels of the groups containing the cell at `row` and `col`.
+    fn groups_at(&self, row: usize, col: usize) -> Vec<String> {
+        let minterm = self.minterm(row, col) as u64;
+        (0..self.groups.len())
+            .filter(|&i| self.groups[i].contains(minterm))
+            .map(group_label)
+            .collect()
+    }
+
+    fn axis_label(&self, vars: &[String]) -> String {
+        if vars.iter().all(|v| v.chars().count() == 1) {
+            vars.concat()
+        } else {
+            vars.join(",")
+        }
+    }
+
+    /// Names of the row and column variables, for the top-left corner.
+    fn corner(&self) -> (String, String) {
+        let (rows, cols) = self.variables.split_at(self.row_vars);
+        (self.axis_label(rows), self.axis_label(cols))
+    }
+
+    /// Renders the map as an HTML table with a legend of the groups.
+    ///
+    /// Cells carry the classes `zero`, `one` or `dont-care`, plus
+    /// `group-<label>` for every group containing them.
+    pub fn to_html(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "<table class=\"kmap\">").unwrap();
+        let (rows, cols) = self.corner();
+        let corner = format!("{}\\{}", rows, cols);
+        write!(out, "  <tr><th>{}</th>", escape_html(&corner)).unwrap();
+        for col in 0..self.cols() {
+            write!(out, "<th>{}</th>", bits(gray(col), self.col_vars())).unwrap();
+        }
+        writeln!(out, "</tr>").unwrap();
+        for row in 0..self.rows() {
+            write!(out, "  <tr><th>{}</th>", bits(gray(row), self.row_vars)).unwrap();
+            for col in 0..self.cols() {
+                let cell = self.cell(row, col);
+                let mut classes = match cell {
+                    KarnaughCell::Zero => "zero",
+                    KarnaughCell::One => "one",
+                    KarnaughCell::DontCare => "dont-care",
+                }
+                .to_string();
+                for label in self.groups_at(row, col) {
+                    write!(classes, " group-{}", label).unwrap();
+                }
+                write!(out, "<td class=\"{}\">{}</td>", classes, cell.symbol()).unwrap();
+            }
+            writeln!(out, "</tr>").unwrap();
+        }
+        writeln!(out, "</table>").unwrap();
+        if !self.groups.is_empty() {
+            writeln!(out, "<ul class=\"kmap-groups\">").unwrap();
+            for (label, term) in self.groups() {
+                writeln!(
+                    out,
+                    "  <li class=\"group-{}\">{}: {}</li>",
+                    label,
+                    label,
+                    escape_html(&term)
+                )
+                .unwrap();
+            }
+            writeln!(out, "</ul>").unwrap();
+        }
+        out
+    }
+
+    /// Renders the map as a LaTeX `tabular`, with groups shown as superscripts
+    /// and listed below the table.
+    pub fn to_latex(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "\\begin{{tabular}}{{c|{}}}", "c".repeat(self.cols())).unwrap();
+        let (rows, cols) = self.corner();
+        write!(
+            out,
+            "${} \\backslash {}$",
+            latex_math(&rows),
+            latex_math(&cols)
+        )
+        .unwrap();
+        for col in 0..self.cols() {
+            write!(out, " & {}", bits(gray(col), self.col_vars())).unwrap();
+        }
+        writeln!(out, " \\\\ \\hline").unwrap();
+        for row in 0..self.rows() {
+            write!(out, "{}", bits(gray(row), self.row_vars)).unwrap();
+            for col in 0..self.cols() {
+                write!(out, " & {}", self.cell(row, col).symbol()).unwrap();
+                let groups = self.groups_at(row, col);
+                if !groups.is_empty() {
+                    write!(out, "$^{{{}}}$", groups.concat()).unwrap();
+                }
+            }
+            writeln!(out, " \\\\").unwrap();
+        }
+        writeln!(out, "\\end{{tabular}}").unwrap();
+        for (label, term) in self.groups() {
+            writeln!(out, "\n{}: ${}$", label, latex_math(&term)).unwrap();
+        }
+        out
+    }
+}
+
+/// Plain text rendering, with the groups containing each cell after its value
+/// and a legend of the groups below the map.
+impl fmt::Display for KarnaughMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cells: Vec<Vec<String>> = (0..self.rows())
+            .map(|row| {
+                (0..self.cols())
+                    .map(|col| {
+                        let groups = self.groups_at(row, col);
+                        let symbol = self.cell(row, col).symbol();
+                        if groups.is_empty() {
+                            symbol.to_string()
+                        } else {
+                            format!("{}:{}", symbol, groups.concat())
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        let width = cells
+            .iter()
+            .flatten()
+            .map(|c| c.chars().count())
+            .chain([self.col_vars()])
+            .max()
+            .unwrap();
+        let (rows, cols) = self.corner();
+        let corner = format!("{}\\{}", rows, cols);
+        let corner_width = corner.chars().count().max(self.row_vars);
+
+        write!(f, "{:>w$} │", corner, w = corner_width)?;
+        for col in 0..self.cols() {
+            write!(f, " {:>w$}", bits(gray(col), self.col_vars()), w = width)?;
+        }
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{}─┼{}",
+            "─".repeat(corner_width),
+            "─".repeat((width + 1) * self.cols())
+        )?;
+        for (row, cells) in cells.iter().enumerate() {
+            write!(
+                f,
+                "{:>w$} │",
+                bits(gray(row), self.row_vars),
+                w = corner_width
+            )?;
+            for cell in cells {
+                write!(f, " {:>w$}", cell, w = width)?;
+            }
+            writeln!(f)?;
+        }
+        for (label, term) in self.groups() {
+            writeln!(f, "{}: {}", label, term)?;
+        }
+        Ok(())
+    }
+}
+
+impl Formula {
+    /// Builds the Karnaugh map of the formula, with `X` on the rows where
+    /// `dont_care` holds.
+    pub fn karnaugh_map(
+        &self,
+        dont_care: Option<&Formula>,
+    ) -> Result<KarnaughMap, KarnaughMapError> {
+        let never = Formula::from_root(Node::Value(false));
+        let dont_care = dont_care.unwrap_or(&never);
+        let variables = self.variables_with(dont_care);
+        if !(2..=6).contains(&variables.len()) {
+            return Err(KarnaughMapError {
+                variables: variables.len(),
+            });
+        }
+        let values = self.truth_vector_over(&variables);
+        let dont_cares = dont_care.truth_vector_over(&variables);
+
+        let row_vars = variables.len() / 2;
+        let col_vars = variables.len() - row_vars;
+        let cells = (0..1 << row_vars)
+            .map(|row| {
+                (0..1 << col_vars)
+                    .map(|col| {
+                        let minterm = gray(row) << col_vars | gray(col);
+                        match (dont_cares[minterm], values[minterm]) {
+                            (true, _) => KarnaughCell::DontCare,
+                            (false, true) => KarnaughCell::One,
+                            (false, false) => KarnaughCell::Zero,
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        Ok(KarnaughMap {
+            variables: variables.into_iter().map(String::from).collect(),
+            row_vars,
+            cells,
+            groups: Vec::new(),
+        })
+    }
+}
@@ -1,21 +1,57 @@
 use std::{
-    collections::{HashMap, HashSet, VecDeque},
+    collections::{HashMap, HashSet},
     fmt::Display,
+    hash::{Hash, Hasher},
 };
 
+mod anf;
+mod ast;
+mod bdd;
+mod compiled;
+mod compose;
+mod cube;
+mod dag;
+mod dimacs;
+mod espresso;
+mod kmap;
 mod lexer;
+mod nnf;
+mod normal_form;
 mod operator;
 mod parser;
+mod qm;
+mod sat;
+mod simplify;
+mod symbol;
+mod synthesis;
+mod truth_vector;
+mod tseitin;
+mod visit;
 
 #[cfg(test)]
 mod test;
 
 type NodeChild = Box<Node>;
 
+pub use anf::Anf;
+pub use bdd::{Bdd, BddManager, BddOp, ReorderReport, Reordering};
+pub use compiled::CompiledFormula;
+pub use dag::{Dag, DagNode, NodeId};
+pub use dimacs::DimacsError;
+pub use espresso::MinimizeError;
+pub use kmap::{KarnaughCell, KarnaughMap, KarnaughMapError};
+pub use normal_form::{Cnf, Dnf, Literal, SizeLimitExceeded};
 pub use parser::FormulaParser;
+pub use sat::Models;
+pub use simplify::{Law, Step};
+pub use symbol::{Symbol, SymbolTable};
+pub use synthesis::{CsvError, Specification, TermOutOfRange, TruthVectorError};
+pub use truth_vector::{Classification, TruthVector, MAX_TABLE_VARIABLES};
+pub use tseitin::Tseitin;
+pub use visit::{Fold, Rewriter, Visitor};
 
 // And, not, or, if, iff
-#[derive(Debug)]
+#[derive(Eq)]
 pub enum Node {
     And(NodeChild, NodeChild), // &
     Or(NodeChild, NodeChild),  // |
@@ -31,8 +67,101 @@ impl Display for Node {
         self.fmt_with_precedence(f, 0)
     }
 }
+
+/// Writes the same text as a derived `Debug`, e.g. `Not(Atom("a"))`, always
+/// on one line.
+impl fmt::Debug for Node {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        struct Writer<'f, 'g> {
+            f: &'f mut fmt::Formatter<'g>,
+            /// How many operands of each open node have been written.
+            written: Vec<usize>,
+            result: fmt::Result,
+        }
+
+        impl Writer<'_, '_> {
+            fn write(&mut self, args: fmt::Arguments<'_>) {
+                if self.result.is_ok() {
+                    self.result = self.f.write_fmt(args);
+                }
+            }
+        }
+
+        impl<'a> Visitor<'a> for Writer<'_, '_> {
+            fn pre(&mut self, node: &'a Node) -> bool {
+                if let Some(written) = self.written.last_mut() {
+                    *written += 1;
+                    if *written > 1 {
+                        self.write(format_args!(", "));
+                    }
+                }
+                let name = match node {
+                    Node::Atom(name) => {
+                        self.write(format_args!("Atom({:?})", name));
+                        return false;
+                    }
+                    Node::Value(b) => {
+                        self.write(format_args!("Value({:?})", b));
+                        return false;
+                    }
+                    Node::And(_, _) => "And",
+                    Node::Or(_, _) => "Or",
+                    Node::Not(_) => "Not",
+                    Node::If(_, _) => "If",
+                    Node::Iff(_, _) => "Iff",
+                };
+                self.write(format_args!("{}(", name));
+                self.written.push(0);
+                self.result.is_ok()
+            }
+
+            fn post(&mut self, _: &'a Node) {
+                self.written.pop();
+                self.write(format_args!(")"));
+            }
+        }
+
+        let mut writer = Writer {
+            f,
+            written: Vec::new(),
+            result: Ok(()),
+        };
+        self.walk(&mut writer);
+        writer.result
+    }
+}
 use std::fmt;
 impl Node {
+    pub fn atom(name: impl Into<String>) -> Node {
+        Node::Atom(name.into())
+    }
+
+    pub fn value(value: bool) -> Node {
+        Node::Value(value)
+    }
+
+    // Named like `and` and `or`; `!node` does the same through `Not`
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(operand: Node) -> Node {
+        Node::Not(Box::new(operand))
+    }
+
+    pub fn and(left: Node, right: Node) -> Node {
+        Node::And(Box::new(left), Box::new(right))
+    }
+
+    pub fn or(left: Node, right: Node) -> Node {
+        Node::Or(Box::new(left), Box::new(right))
+    }
+
+    pub fn implies(left: Node, right: Node) -> Node {
+        Node::If(Box::new(left), Box::new(right))
+    }
+
+    pub fn iff(left: Node, right: Node) -> Node {
+        Node::Iff(Box::new(left), Box::new(right))
+    }
+
     fn precedence(&self) -> u8 {
         match self {
             Node::Atom(_) | Node::Value(_) => 5,
@@ -44,76 +173,187 @@ impl Node {
         }
     }
 
+    /// Writes the node, parenthesizing it if it binds less tightly than
+    /// its parent.
     fn fmt_with_precedence(
         &self,
         f: &mut fmt::Formatter<'_>,
         parent_precedence: u8,
     ) -> fmt::Result {
-        let this_precedence = self.precedence();
-        let need_parens = this_precedence < parent_precedence;
-
-        if need_parens {
-            write!(f, "(")?;
+        enum Item<'a> {
+            Node(&'a Node, u8),
+            Text(&'a str),
         }
 
-        match self {
-            Node::And(left, right) => {
-                left.fmt_with_precedence(f, this_precedence)?;
-                write!(f, " ∧ ")?;
-                right.fmt_with_precedence(f, this_precedence)?;
-            }
-            Node::Or(left, right) => {
-                left.fmt_with_precedence(f, this_precedence)?;
-                write!(f, " ∨ ")?;
-                right.fmt_with_precedence(f, this_precedence)?;
-            }
-            Node::Not(operand) => {
-                write!(f, "¬")?;
-                operand.fmt_with_precedence(f, this_precedence)?;
-            }
-            Node::If(left, right) => {
-                left.fmt_with_precedence(f, this_precedence)?;
-                write!(f, " ⇒ ")?;
-                right.fmt_with_precedence(f, this_precedence)?;
+        let mut stack = vec![Item::Node(self, parent_precedence)];
+        while let Some(item) = stack.pop() {
+            let (node, parent_precedence) = match item {
+                Item::Text(text) => {
+                    write!(f, "{}", text)?;
+                    continue;
+                }
+                Item::Node(node, precedence) => (node, precedence),
+            };
+            let this_precedence = node.precedence();
+            let need_parens = this_precedence < parent_precedence;
+
+            if need_parens {
+                write!(f, "(")?;
+                stack.push(Item::Text(")"));
             }
-            Node::Iff(left, right) => {
-                left.fmt_with_precedence(f, this_precedence)?;
-                write!(f, " ⇔ ")?;
-                right.fmt_with_precedence(f, this_precedence)?;
+
+            // Pushed in reverse, so the left operand is written first
+            let (left, symbol, right) = match node {
+                Node::And(left, right) => (left, " ∧ ", right),
+                Node::Or(left, right) => (left, " ∨ ", right),
+                Node::If(left, right) => (left, " ⇒ ", right),
+                Node::Iff(left, right) => (left, " ⇔ ", right),
+                Node::Not(operand) => {
+                    write!(f, "¬")?;
+                    stack.push(Item::Node(operand, this_precedence));
+                    continue;
+                }
+                Node::Atom(s) => {
+                    write!(f, "{}", s)?;
+                    continue;
+                }
+                Node::Value(b) => {
+                    write!(f, "{}", if *b { "T" } else { "F" })?;
+                    continue;
+                }
+            };
+            stack.push(Item::Node(right, this_precedence));
+            stack.push(Item::Text(symbol));
+            stack.push(Item::Node(left, this_precedence));
+        }
+
+        Ok(())
+    }
+
+    /// Returns every descendant of the node, not including itself, in
+    /// pre-order.
+    pub fn children(&self) -> Vec<&Node> {
+        struct Descendants<'a> {
+            root: &'a Node,
+            nodes: Vec<&'a Node>,
+        }
+
+        impl<'a> Visitor<'a> for Descendants<'a> {
+            fn pre(&mut self, node: &'a Node) -> bool {
+                if !std::ptr::eq(node, self.root) {
+                    self.nodes.push(node);
+                }
+                true
             }
-            Node::Atom(s) => write!(f, "{}", s)?,
-            Node::Value(b) => write!(f, "{}", if *b { "T" } else { "F" })?,
         }
 
-        if need_parens {
-            write!(f, ")")?;
+        let mut descendants = Descendants {
+            root: self,
+            nodes: Vec::new(),
+        };
+        self.walk(&mut descendants);
+        descendants.nodes
+    }
+}
+
+/// Cloning rebuilds the tree bottom-up with [`Node::walk`].
+impl Clone for Node {
+    fn clone(&self) -> Node {
+        struct Cloner(Vec<Node>);
+
+        impl<'a> Visitor<'a> for Cloner {
+            fn post(&mut self, node: &'a Node) {
+                let node = match node {
+                    Node::Atom(name) => Node::Atom(name.clone()),
+                    Node::Value(b) => Node::Value(*b),
+                    Node::Not(_) => Node::not(self.0.pop().unwrap()),
+                    _ => {
+                        let right = self.0.pop().unwrap();
+                        let left = self.0.pop().unwrap();
+                        match node {
+                            Node::And(_, _) => Node::and(left, right),
+                            Node::Or(_, _) => Node::or(left, right),
+                            Node::If(_, _) => Node::implies(left, right),
+                            _ => Node::iff(left, right),
+                        }
+                    }
+                };
+                self.0.push(node);
+            }
         }
 
-        Ok(())
+        let mut cloner = Cloner(Vec::new());
+        self.walk(&mut cloner);
+        cloner.0.pop().unwrap()
     }
+}
 
-    fn _children<'a>(&'a self, stack: &mut Vec<&'a Node>) {
-        match self {
-            Node::And(left, right)
-            | Node::If(left, right)
-            | Node::Or(left, right)
-            | Node::Iff(left, right) => {
-                stack.push(left);
-                left._children(stack);
-                stack.push(right);
-                right._children(stack);
+/// Structural equality, comparing the trees side by side.
+impl PartialEq for Node {
+    fn eq(&self, other: &Node) -> bool {
+        let mut pending = vec![(self, other)];
+        while let Some(pair) = pending.pop() {
+            match pair {
+                (Node::And(a, b), Node::And(c, d))
+                | (Node::Or(a, b), Node::Or(c, d))
+                | (Node::If(a, b), Node::If(c, d))
+                | (Node::Iff(a, b), Node::Iff(c, d)) => {
+                    pending.push((b, d));
+                    pending.push((a, c));
+                }
+                (Node::Not(a), Node::Not(b)) => pending.push((a, b)),
+                (Node::Atom(a), Node::Atom(b)) if a == b => {}
+                (Node::Value(a), Node::Value(b)) if a == b => {}
+                _ => return false,
             }
-            Node::Not(operand) => {
-                stack.push(operand);
-                operand._children(stack);
+        }
+        true
+    }
+}
+
+/// Hashes the kind and contents of every node in pre-order, which determines
+/// the tree.
+impl Hash for Node {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        struct Hashing<'h, H>(&'h mut H);
+
+        impl<'a, H: Hasher> Visitor<'a> for Hashing<'_, H> {
+            fn pre(&mut self, node: &'a Node) -> bool {
+                std::mem::discriminant(node).hash(self.0);
+                match node {
+                    Node::Atom(name) => name.hash(self.0),
+                    Node::Value(b) => b.hash(self.0),
+                    _ => {}
+                }
+                true
             }
-            _ => {}
         }
+
+        self.walk(&mut Hashing(state));
     }
-    fn children(&self) -> Vec<&Node> {
-        let mut stack = Vec::new();
-        self._children(&mut stack);
-        stack
+}
+
+impl std::ops::Not for Node {
+    type Output = Node;
+
+    fn not(self) -> Node {
+        Node::not(self)
+    }
+}
+
+impl std::ops::BitAnd for Node {
+    type Output = Node;
+
+    fn bitand(self, rhs: Node) -> Node {
+        Node::and(self, rhs)
+    }
+}
+
+impl std::ops::BitOr for Node {
+    type Output = Node;
+
+    fn bitor(self, rhs: Node) -> Node {
+        Node::or(self, rhs)
     }
 }
 
@@ -124,73 +364,290 @@ impl From<&str> for Formula {
     }
 }
 
-#[derive(Debug)]
+/// Builds a formula around `root`, collecting its variables.
+impl From<Node> for Formula {
+    fn from(root: Node) -> Self {
+        Formula::from_root(root)
+    }
+}
+
+#[derive(Clone, Debug)]
 pub struct Formula {
     root: Node,
     pub variables: HashSet<String>,
 }
 
+/// Evaluates a tree bottom-up. The right operand of `∧`, `∨` and `⇒` is
+/// skipped when the left one decides the result.
+struct Evaluator<'a, 'v> {
+    vars: &'v HashMap<String, bool>,
+    /// The operators whose operands are being evaluated, innermost last.
+    parents: Vec<&'a Node>,
+    values: Vec<bool>,
+    /// Set once a reached atom is missing from `vars`.
+    missing: bool,
+}
+
+impl<'a> Visitor<'a> for Evaluator<'a, '_> {
+    fn pre(&mut self, node: &'a Node) -> bool {
+        if self.missing {
+            return false;
+        }
+        if let Some(&parent) = self.parents.last() {
+            let left = self.values.last().copied();
+            let decided = match parent {
+                Node::And(_, right) if std::ptr::eq(node, &**right) => left == Some(false),
+                Node::Or(_, right) if std::ptr::eq(node, &**right) => left == Some(true),
+                Node::If(_, right) if std::ptr::eq(node, &**right) => left == Some(false),
+                _ => false,
+            };
+            // Any value of the skipped operand gives the same result, so
+            // the parent combines the left one with itself
+            if decided {
+                self.values.push(left.unwrap());
+                return false;
+            }
+        }
+        match node {
+            Node::Atom(s) => match self.vars.get(s) {
+                Some(&value) => self.values.push(value),
+                None => self.missing = true,
+            },
+            Node::Value(b) => self.values.push(*b),
+            _ => {
+                self.parents.push(node);
+                return true;
+            }
+        }
+        false
+    }
+
+    fn post(&mut self, node: &'a Node) {
+        self.parents.pop();
+        if self.missing {
+            return;
+        }
+        let right = self.values.pop().unwrap();
+        let value = match node {
+            Node::Not(_) => !right,
+            _ => {
+                let left = self.values.pop().unwrap();
+                match node {
+                    Node::And(_, _) => left && right,
+                    Node::Or(_, _) => left || right,
+                    Node::If(_, _) => !left || right,
+                    _ => left == right,
+                }
+            }
+        };
+        self.values.push(value);
+    }
+}
+
+/// Evaluates `node`, or returns `None` if it needs a variable missing from
+/// `vars`. Operands are evaluated left to right, and `∧`, `∨` and `⇒` skip
+/// their right operand when the left one decides.
 fn eval_node(node: &Node, vars: &HashMap<String, bool>) -> Option<bool> {
-    let out = match node {
-        Node::And(left, right) => eval_node(left, vars)? && eval_node(right, vars)?,
-        Node::Or(left, right) => eval_node(left, vars)? || eval_node(right, vars)?,
-        Node::Not(operand) => !eval_node(operand, vars)?,
-        Node::If(left, right) => !eval_node(left, vars)? || eval_node(right, vars)?,
-        Node::Iff(left, right) => eval_node(left, vars)? == eval_node(right, vars)?,
-        Node::Atom(s) => return vars.get(s).copied(),
-        Node::Value(b) => *b,
+    let mut evaluator = Evaluator {
+        vars,
+        parents: Vec::new(),
+        values: Vec::new(),
+        missing: false,
     };
-    Some(out)
+    node.walk(&mut evaluator);
+    if evaluator.missing {
+        None
+    } else {
+        evaluator.values.pop()
+    }
+}
+
+/// Dropping a node frees its descendants from an explicit stack instead of
+/// recursing through the boxes.
+impl Drop for Node {
+    fn drop(&mut self) {
+        /// Moves the operands of `node` that have operands of their own
+        /// onto `stack`, leaving leaves in their place.
+        fn take(node: &mut Node, stack: &mut Vec<Node>) {
+            let mut take_operand = |operand: &mut Node| {
+                if !matches!(operand, Node::Atom(_) | Node::Value(_)) {
+                    stack.push(std::mem::replace(operand, Node::Value(false)));
+                }
+            };
+            match node {
+                Node::And(left, right)
+                | Node::Or(left, right)
+                | Node::If(left, right)
+                | Node::Iff(left, right) => {
+                    take_operand(left);
+                    take_operand(right);
+                }
+                Node::Not(operand) => take_operand(operand),
+                Node::Atom(_) | Node::Value(_) => {}
+            }
+        }
+
+        let mut stack = Vec::new();
+        take(self, &mut stack);
+        // Each popped node is emptied before it drops, so its own `drop`
+        // only sees leaves
+        while let Some(mut node) = stack.pop() {
+            take(&mut node, &mut stack);
+        }
+    }
 }
 
 impl Formula {
+    /// Builds a formula around `root`, collecting its variables.
+    pub(crate) fn from_root(root: Node) -> Formula {
+        struct Variables(HashSet<String>);
+
+        impl<'a> Visitor<'a> for Variables {
+            fn pre(&mut self, node: &'a Node) -> bool {
+                if let Node::Atom(s) = node {
+                    self.0.insert(s.clone());
+                }
+                true
+            }
+        }
+
+        let mut variables = Variables(HashSet::new());
+        root.walk(&mut variables);
+        Formula {
+            variables: variables.0,
+            root,
+        }
+    }
+
+    /// The root of the syntax tree.
+    pub fn root(&self) -> &Node {
+        &self.root
+    }
+
+    /// Takes the syntax tree, e.g. to [fold](Node::fold) it without a copy.
+    pub fn into_root(self) -> Node {
+        self.root
+    }
+
     pub fn eval(&self, vars: &HashMap<String, bool>) -> Option<bool> {
         eval_node(&self.root, vars)
     }
 
+    /// Returns the variables in the order used by the truth table.
+    pub fn sorted_variables(&self) -> Vec<&str> {
+        let mut variables: Vec<_> = self.variables.iter().map(String::as_str).collect();
+        variables.sort_unstable();
+        variables
+    }
+
+    /// Evaluates every row of the truth table.
+    ///
+    /// Entry `i` is the value for minterm `i`, where the first of the
+    /// [sorted variables](Formula::sorted_variables) is the most significant
+    /// bit. The truth table lists the rows from the last entry to the first.
+    pub fn truth_vector(&self) -> Vec<bool> {
+        self.truth_vector_over(&self.sorted_variables())
+    }
+
+    /// Evaluates every row of the truth table over `variables`, which must
+    /// include all of the formula's variables.
+    pub(crate) fn truth_vector_over(&self, variables: &[&str]) -> Vec<bool> {
+        self.compile_over(variables).truth_vector()
+    }
+
+    /// Returns the sorted union of the variables of both formulas.
+    pub(crate) fn variables_with<'a>(&'a self, other: &'a Formula) -> Vec<&'a str> {
+        let mut variables: Vec<_> = self
+            .variables
+            .union(&other.variables)
+            .map(String::as_str)
+            .collect();
+        variables.sort_unstable();
+        variables
+    }
+
+    /// Returns a formula that holds exactly on the given rows of this
+    /// formula's truth table, numbered as in [`Formula::truth_vector`].
+    ///
+    /// This is convenient for listing don't-care rows by index. Returns an
+    /// error if a row is past the end of the table.
+    pub fn select_rows(&self, rows: &[usize]) -> Result<Formula, TermOutOfRange> {
+        Formula::from_minterms(&self.sorted_variables(), rows)
+    }
+
+    /// Lazily enumerates every satisfying assignment, in truth table order.
+    pub fn models(&self) -> Models {
+        Models::new(self, &self.sorted_variables())
+    }
+
+    /// Enumerates the distinct satisfying assignments of `variables` only.
+    ///
+    /// Two models that differ only on variables outside the projection are
+    /// reported once. Variables not occurring in the formula are free.
+    pub fn models_projected(&self, variables: &[&str]) -> Models {
+        Models::new(self, variables)
+    }
+
     pub fn print_truth_table(&self) {
-      let mut variables = self.variables.iter().collect::<Vec<_>>();
-      variables.sort_unstable();
-  
-      // Print header
-      print!("| ");
-      for var in &variables {
-          print!("{} | ", var);
-      }
-      let root_str = format!("{}", self.root);
-      println!("{} |", root_str);
-  
-      // Print separator
-      print!("|");
-      for _ in &variables {
-          print!(":-:|");
-      }
-      println!(":-:|");
-  
-      let num_vars = variables.len();
-      let num_rows = 1 << num_vars;
-      let mut vars = HashMap::new();
-  
-      for i in (0..num_rows).rev() {
-          print!("| ");
-          for (j, var) in variables.iter().enumerate() {
-              let value = (i >> (num_vars - 1 - j)) & 1 == 1;
-              vars.insert(var.to_string(), value);
-              let value_str = if value { "T" } else { "F" };
-              print!("{} | ", value_str);
-          }
-  
-          // Evaluate and print result
-          let result_str = match self.eval(&vars) {
-              Some(true) => "T",
-              Some(false) => "F",
-              None => "E",
-          };
-          println!("{} |", result_str);
-      }
-  
-      println!("\nT: True, F: False, E: Error (undefined variable)");
-  }
+        self.print_table(None)
+    }
+
+    /// Prints the truth table, showing `X` on the rows where `dont_care` holds.
+    pub fn print_truth_table_with_dont_cares(&self, dont_care: &Formula) {
+        self.print_table(Some(dont_care))
+    }
+
+    fn print_table(&self, dont_care: Option<&Formula>) {
+        let mut variables = self.variables.iter().collect::<Vec<_>>();
+        if let Some(dont_care) = dont_care {
+            variables.extend(dont_care.variables.difference(&self.variables));
+        }
+        variables.sort_unstable();
+
+        // Print header
+        print!("| ");
+        for var in &variables {
+            print!("{} | ", var);
+        }
+        let root_str = format!("{}", self.root);
+        println!("{} |", root_str);
+
+        // Print separator
+        print!("|");
+        for _ in &variables {
+            print!(":-:|");
+        }
+        println!(":-:|");
+
+        let variables: Vec<_> = variables.into_iter().map(String::as_str).collect();
+        let values = self.compile_over(&variables).truth_bits();
+        let dont_care = dont_care.map(|dc| dc.compile_over(&variables).truth_bits());
+        let num_vars = variables.len();
+        let num_rows = truth_vector::row_count(num_vars);
+
+        for i in (0..num_rows).rev() {
+            print!("| ");
+            for j in 0..num_vars {
+                let value = (i >> (num_vars - 1 - j)) & 1 == 1;
+                let value_str = if value { "T" } else { "F" };
+                print!("{} | ", value_str);
+            }
+
+            // Evaluate and print result
+            if dont_care.as_ref().is_some_and(|dc| dc.get(i)) {
+                println!("X |");
+                continue;
+            }
+            let result_str = if values.get(i) { "T" } else { "F" };
+            println!("{} |", result_str);
+        }
+
+        if dont_care.is_some() {
+            println!("\nT: True, F: False, X: Don't care");
+        } else {
+            println!("\nT: True, F: False");
+        }
+    }
 }
 
 impl std::fmt::Display for Formula {
@@ -1,26 +1,352 @@
-use truth_table_rs::{Formula, FormulaParser};
+use truth_table_rs::{
+    BddManager, CompiledFormula, Formula, FormulaParser, Specification, SymbolTable,
+    MAX_TABLE_VARIABLES,
+};
+
+/// Clause limit before `--output dimacs` falls back to a Tseitin encoding.
+const DIMACS_CLAUSE_LIMIT: usize = 10_000;
+
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+enum Input {
+    #[default]
+    Formula,
+    Dimacs,
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+enum Output {
+    #[default]
+    Table,
+    Dimacs,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum KmapFormat {
+    Text,
+    Html,
+    Latex,
+}
+
+#[derive(Default)]
+struct Options {
+    formulas: Vec<String>,
+    models: bool,
+    project: Option<Vec<String>>,
+    input: Input,
+    output: Output,
+    bdd_dot: Option<String>,
+    ast: bool,
+    dont_care: Option<String>,
+    minimize: bool,
+    kmap: Option<KmapFormat>,
+    groups: bool,
+    from_csv: Option<String>,
+    anf: bool,
+    simplify: bool,
+    classify: bool,
+}
+
+fn fail(message: impl std::fmt::Display) -> ! {
+    eprintln!("error: {}", message);
+    std::process::exit(1)
+}
+
+impl Options {
+    fn from_args(mut args: impl Iterator<Item = String>) -> Options {
+        let mut options = Options::default();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--models" => options.models = true,
+                "--ast" => options.ast = true,
+                "--minimize" => options.minimize = true,
+                "--groups" => options.groups = true,
+                "--anf" => options.anf = true,
+                "--simplify" => options.simplify = true,
+                "--classify" => options.classify = true,
+                "--kmap" => {
+                    options.kmap = match args.next().as_deref() {
+                        Some("text") => Some(KmapFormat::Text),
+                        Some("html") => Some(KmapFormat::Html),
+                        Some("latex") => Some(KmapFormat::Latex),
+                        _ => fail("--kmap expects `text`, `html` or `latex`"),
+                    }
+                }
+                "--dont-care" => {
+                    let Some(formula) = args.next() else {
+                        fail("--dont-care expects a formula")
+                    };
+                    options.dont_care = Some(formula);
+                }
+                "--project" => {
+                    let Some(vars) = args.next() else {
+                        fail("--project expects a list of variables")
+                    };
+                    options.project = Some(
+                        vars.split(',')
+                            .map(str::trim)
+                            .filter(|v| !v.is_empty())
+                            .map(String::from)
+                            .collect(),
+                    );
+                }
+                "--input" => {
+                    options.input = match args.next().as_deref() {
+                        Some("formula") => Input::Formula,
+                        Some("dimacs") => Input::Dimacs,
+                        _ => fail("--input expects `formula` or `dimacs`"),
+                    }
+                }
+                "--output" => {
+                    options.output = match args.next().as_deref() {
+                        Some("table") => Output::Table,
+                        Some("dimacs") => Output::Dimacs,
+                        _ => fail("--output expects `table` or `dimacs`"),
+                    }
+                }
+                "--from-csv" => {
+                    let Some(path) = args.next() else {
+                        fail("--from-csv expects a CSV file")
+                    };
+                    options.from_csv = Some(path);
+                }
+                "--bdd-dot" => {
+                    let Some(path) = args.next() else {
+                        fail("--bdd-dot expects an output file")
+                    };
+                    options.bdd_dot = Some(path);
+                }
+                _ => options.formulas.push(arg),
+            }
+        }
+        options
+    }
+}
 
 fn main() {
     // let source = "((A & B) | C)";
-    let source: Vec<String> = std::env::args().skip(1).collect();
-    let formulas: Vec<_> = source
-        .iter()
-        .map(|e| e.as_str())
-        .map(FormulaParser::new)
-        .map(FormulaParser::parse)
-        .collect();
+    let options = Options::from_args(std::env::args().skip(1));
+    if let Some(path) = &options.from_csv {
+        let spec = read_csv(path);
+        if options.minimize {
+            println!("{}", minimize(&spec.formula, Some(&spec.dont_care)));
+        } else {
+            println!("{}", spec.formula);
+        }
+        return;
+    }
+
+    let formulas: Vec<_> = match options.input {
+        Input::Formula => options
+            .formulas
+            .iter()
+            .map(|e| e.as_str())
+            .map(FormulaParser::new)
+            .map(FormulaParser::parse)
+            .collect(),
+        Input::Dimacs => options
+            .formulas
+            .iter()
+            .map(|path| read_dimacs(path))
+            .collect(),
+    };
+
+    if let Some(path) = &options.bdd_dot {
+        write_bdd_dot(&formulas, path);
+    }
+
+    let dont_care = options
+        .dont_care
+        .as_deref()
+        .map(|source| FormulaParser::new(source).parse());
+
+    if options.minimize {
+        for formula in &formulas {
+            println!("{}", minimize(formula, dont_care.as_ref()));
+        }
+        return;
+    }
+
+    if let Some(format) = options.kmap {
+        for formula in &formulas {
+            let map = formula
+                .karnaugh_map(dont_care.as_ref())
+                .unwrap_or_else(|e| fail(format_args!("{}: {}", formula, e)));
+            let map = if options.groups {
+                map.with_groups()
+            } else {
+                map
+            };
+            match format {
+                KmapFormat::Text => println!("{}", map),
+                KmapFormat::Html => println!("{}", map.to_html()),
+                KmapFormat::Latex => println!("{}", map.to_latex()),
+            }
+        }
+        return;
+    }
+
+    if options.classify {
+        for formula in &formulas {
+            check_table_width(formula.variables.len());
+            let models = formula.count_models();
+            let rows = 1u64 << formula.variables.len();
+            println!(
+                "{}: {}, {} of {} rows",
+                formula,
+                formula.classify(),
+                models,
+                rows
+            );
+        }
+        return;
+    }
+
+    if options.simplify {
+        for formula in &formulas {
+            let (simplified, steps) = formula.simplify_with_steps();
+            println!("{}", formula);
+            for step in steps {
+                println!("  {}", step);
+            }
+            println!("= {}\n", simplified);
+        }
+        return;
+    }
+
+    if options.anf {
+        for formula in &formulas {
+            check_table_width(formula.variables.len());
+            let anf = formula.to_anf();
+            println!("{} (degree {})", anf, anf.degree());
+        }
+        return;
+    }
+
+    if options.ast {
+        for formula in &formulas {
+            println!("{}", formula.to_ascii_tree());
+        }
+        return;
+    }
+
+    if options.output == Output::Dimacs {
+        for formula in &formulas {
+            print!("{}", to_dimacs(formula));
+        }
+        return;
+    }
+
+    if options.models || options.project.is_some() {
+        let project = options
+            .project
+            .as_ref()
+            .map(|vars| vars.iter().map(String::as_str).collect::<Vec<_>>());
+        for formula in &formulas {
+            print_models(formula, project.as_deref());
+        }
+        return;
+    }
 
     // formula.print_truth_table();
-    print_truth_table(&formulas);
+    print_truth_table(&formulas, dont_care.as_ref());
+}
+
+fn minimize(formula: &Formula, dont_care: Option<&Formula>) -> Formula {
+    let never = Formula::from("F");
+    formula
+        .minimize_auto(dont_care.unwrap_or(&never))
+        .unwrap_or_else(|e| fail(format_args!("{}: {}", formula, e)))
 }
 
-fn print_truth_table(formulas: &[Formula]) {
-    let mut all_variables = std::collections::HashSet::new();
+/// Fails unless a truth table over `variables` variables can be built.
+fn check_table_width(variables: usize) {
+    if variables > MAX_TABLE_VARIABLES {
+        fail(format_args!(
+            "{} variables exceed the truth table limit of {}",
+            variables, MAX_TABLE_VARIABLES
+        ));
+    }
+}
+
+fn read_dimacs(path: &str) -> Formula {
+    let source = std::fs::read_to_string(path)
+        .unwrap_or_else(|e| fail(format_args!("cannot read {}: {}", path, e)));
+    Formula::from_dimacs(&source).unwrap_or_else(|e| fail(format_args!("{}: {}", path, e)))
+}
+
+fn read_csv(path: &str) -> Specification {
+    let source = std::fs::read_to_string(path)
+        .unwrap_or_else(|e| fail(format_args!("cannot read {}: {}", path, e)));
+    Specification::from_csv(&source).unwrap_or_else(|e| fail(format_args!("{}: {}", path, e)))
+}
+
+/// Writes the formula's CNF if it is small enough, otherwise an
+/// equisatisfiable Tseitin encoding.
+fn to_dimacs(formula: &Formula) -> String {
+    match formula.to_cnf(DIMACS_CLAUSE_LIMIT) {
+        Ok(cnf) => cnf.to_dimacs(),
+        Err(_) => formula.to_plaisted_greenbaum().to_dimacs(),
+    }
+}
+
+/// Writes the BDDs of all formulas, sharing one manager, as a DOT graph.
+fn write_bdd_dot(formulas: &[Formula], path: &str) {
+    // Variables are ordered as they are first met, each formula's in truth
+    // table order
+    let mut symbols = SymbolTable::new();
     for formula in formulas {
-        all_variables.extend(formula.variables.iter().cloned());
+        formula.intern(&mut symbols);
     }
-    let mut variables: Vec<_> = all_variables.into_iter().collect();
-    variables.sort_unstable();
+    let mut manager = BddManager::with_symbols(&symbols);
+    let labels: Vec<_> = formulas.iter().map(|f| f.to_string()).collect();
+    let roots: Vec<_> = formulas
+        .iter()
+        .zip(&labels)
+        .map(|(formula, label)| (label.as_str(), formula.to_bdd(&mut manager)))
+        .collect();
+    std::fs::write(path, manager.to_dot(&roots))
+        .unwrap_or_else(|e| fail(format_args!("cannot write {}: {}", path, e)));
+}
+
+/// Prints one model per line as a list of literals, e.g. `a ¬b c`.
+fn print_models(formula: &Formula, project: Option<&[&str]>) {
+    let variables = match project {
+        Some(vars) => vars.to_vec(),
+        None => formula.sorted_variables(),
+    };
+    let models = match project {
+        Some(vars) => formula.models_projected(vars),
+        None => formula.models(),
+    };
+
+    println!("{}:", formula);
+    let mut count = 0usize;
+    for model in models {
+        let literals: Vec<_> = variables
+            .iter()
+            .map(|&var| {
+                if model[var] {
+                    var.to_string()
+                } else {
+                    format!("¬{}", var)
+                }
+            })
+            .collect();
+        println!("  {}", literals.join(" "));
+        count += 1;
+    }
+    println!("{} model{}\n", count, if count == 1 { "" } else { "s" });
+}
+
+fn print_truth_table(formulas: &[Formula], dont_care: Option<&Formula>) {
+    // Formulas share one symbol table, so a variable gets one column
+    let mut symbols = SymbolTable::new();
+    for formula in formulas.iter().chain(dont_care) {
+        formula.intern(&mut symbols);
+    }
+    let order = symbols.sorted();
+    let variables: Vec<_> = order.iter().map(|&s| symbols.name(s)).collect();
+    check_table_width(variables.len());
 
     // Print header
     print!("| ");
@@ -42,32 +368,43 @@ fn print_truth_table(formulas: &[Formula]) {
     }
     println!();
 
+    // Every formula's variables are interned, so compiling succeeds
+    let table = |f: &Formula| {
+        CompiledFormula::with_symbol_order(f, &symbols, &order)
+            .unwrap()
+            .truth_bits()
+    };
+    let tables: Vec<_> = formulas.iter().map(table).collect();
+    let dont_care = dont_care.map(table);
     let num_vars = variables.len();
-    let num_rows = 1 << num_vars;
-    let mut vars = std::collections::HashMap::new();
+    let num_rows = 1usize << num_vars;
 
     for i in (0..num_rows).rev() {
         print!("| ");
-        for (j, var) in variables.iter().enumerate() {
+        for j in 0..num_vars {
             let value = (i >> (num_vars - 1 - j)) & 1 == 1;
-            vars.insert(var.to_string(), value);
             let value_str = if value { "T" } else { "F" };
             print!("{} | ", value_str);
         }
 
         // Evaluate and print result for each formula
-        for formula in formulas {
-            let result_str = match formula.eval(&vars) {
-                Some(true) => "T",
-                Some(false) => "F",
-                None => "E",
-            };
+        let is_dont_care = dont_care.as_ref().is_some_and(|dc| dc.get(i));
+        for values in &tables {
+            if is_dont_care {
+                print!("X | ");
+                continue;
+            }
+            let result_str = if values.get(i) { "T" } else { "F" };
             print!("{} | ", result_str);
         }
         println!();
     }
 
-    println!("\nT: True, F: False, E: Error (undefined variable)");
+    if dont_care.is_some() {
+        println!("\nT: True, F: False, X: Don't care");
+    } else {
+        println!("\nT: True, F: False");
+    }
 
     // Print expressions
 }
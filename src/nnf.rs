@@ -0,0 +1,97 @@
+use crate::{visit::Polarities, Formula, Node, Visitor};
+
+fn and(left: Node, right: Node) -> Node {
+    match (left, right) {
+        (Node::Value(false), _) | (_, Node::Value(false)) => Node::Value(false),
+        (Node::Value(true), other) | (other, Node::Value(true)) => other,
+        (left, right) => Node::And(Box::new(left), Box::new(right)),
+    }
+}
+
+fn or(left: Node, right: Node) -> Node {
+    match (left, right) {
+        (Node::Value(true), _) | (_, Node::Value(true)) => Node::Value(true),
+        (Node::Value(false), other) | (other, Node::Value(false)) => other,
+        (left, right) => Node::Or(Box::new(left), Box::new(right)),
+    }
+}
+
+/// Converts every node into negation normal form, in each polarity it occurs
+/// with.
+#[derive(Default)]
+struct Nnf<'a> {
+    polarities: Polarities<'a>,
+    /// The converted operands, each as itself and negated where needed.
+    done: Vec<[Option<Node>; 2]>,
+}
+
+impl<'a> Visitor<'a> for Nnf<'a> {
+    fn pre(&mut self, node: &'a Node) -> bool {
+        self.polarities.enter(node);
+        true
+    }
+
+    fn post(&mut self, node: &'a Node) {
+        let [pos, neg] = self.polarities.exit();
+        let converted = match node {
+            Node::Atom(s) => [
+                pos.then(|| Node::Atom(s.clone())),
+                neg.then(|| Node::not(Node::Atom(s.clone()))),
+            ],
+            Node::Value(b) => [pos.then(|| Node::Value(*b)), neg.then(|| Node::Value(!b))],
+            Node::Not(_) => {
+                let [operand_pos, operand_neg] = self.done.pop().unwrap();
+                [operand_neg, operand_pos]
+            }
+            _ => {
+                let [rp, rn] = self.done.pop().unwrap();
+                let [lp, ln] = self.done.pop().unwrap();
+                let get = |node: Option<Node>| node.expect("polarity was requested");
+                match node {
+                    Node::And(_, _) => [
+                        pos.then(|| and(get(lp), get(rp))),
+                        neg.then(|| or(get(ln), get(rn))),
+                    ],
+                    Node::Or(_, _) => [
+                        pos.then(|| or(get(lp), get(rp))),
+                        neg.then(|| and(get(ln), get(rn))),
+                    ],
+                    // a ⇒ b  ≡  ¬a ∨ b,  ¬(a ⇒ b)  ≡  a ∧ ¬b
+                    Node::If(_, _) => [
+                        pos.then(|| or(get(ln), get(rp))),
+                        neg.then(|| and(get(lp), get(rn))),
+                    ],
+                    // a ⇔ b  ≡  (a ∧ b) ∨ (¬a ∧ ¬b),  ¬(a ⇔ b)  ≡  (a ∧ ¬b) ∨ (¬a ∧ b)
+                    _ => {
+                        let (lp, ln, rp, rn) = (get(lp), get(ln), get(rp), get(rn));
+                        match (pos, neg) {
+                            (true, false) => [Some(or(and(lp, rp), and(ln, rn))), None],
+                            (false, true) => [None, Some(or(and(lp, rn), and(ln, rp)))],
+                            _ => {
+                                let iff =
+                                    or(and(lp.clone(), rp.clone()), and(ln.clone(), rn.clone()));
+                                [Some(iff), Some(or(and(lp, rn), and(ln, rp)))]
+                            }
+                        }
+                    }
+                }
+            }
+        };
+        self.done.push(converted);
+    }
+}
+
+impl Formula {
+    /// Returns an equivalent formula in negation normal form.
+    ///
+    /// Implications and biconditionals are eliminated, negations are pushed
+    /// down to the atoms using De Morgan's laws and constants are folded away,
+    /// so the result is either a single constant or contains only `∧`, `∨` and
+    /// negated atoms.
+    pub fn to_nnf(&self) -> Formula {
+        let mut nnf = Nnf::default();
+        self.root.walk(&mut nnf);
+        let [root, _] = nnf.done.pop().unwrap();
+        Formula::from_root(root.unwrap())
+    }
+}
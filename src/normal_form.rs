@@ -0,0 +1,388 @@
+use std::fmt;
+
+use crate::{
+    visit::{Polarities, Polarity},
+    Formula, Node, Visitor,
+};
+
+/// An atom or its negation.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Literal {
+    pub name: String,
+    pub positive: bool,
+}
+
+impl Literal {
+    pub fn new(name: impl Into<String>, positive: bool) -> Literal {
+        Literal {
+            name: name.into(),
+            positive,
+        }
+    }
+
+    fn to_node(&self) -> Node {
+        let atom = Node::Atom(self.name.clone());
+        if self.positive {
+            atom
+        } else {
+            Node::Not(Box::new(atom))
+        }
+    }
+}
+
+impl std::ops::Not for Literal {
+    type Output = Literal;
+
+    fn not(self) -> Literal {
+        Literal {
+            positive: !self.positive,
+            ..self
+        }
+    }
+}
+
+impl fmt::Display for Literal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.positive {
+            write!(f, "¬")?;
+        }
+        write!(f, "{}", self.name)
+    }
+}
+
+/// Conjunction of clauses, each a disjunction of literals.
+///
+/// An empty clause list is true, and an empty clause is false.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cnf {
+    pub clauses: Vec<Vec<Literal>>,
+}
+
+/// Disjunction of terms, each a conjunction of literals.
+///
+/// An empty term list is false, and an empty term is true.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Dnf {
+    pub terms: Vec<Vec<Literal>>,
+}
+
+/// Returned when a clausal form would hold more clauses than allowed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SizeLimitExceeded {
+    pub limit: usize,
+}
+
+impl fmt::Display for SizeLimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "normal form exceeds the limit of {} clauses", self.limit)
+    }
+}
+
+impl std::error::Error for SizeLimitExceeded {}
+
+type Clauses = Result<Vec<Vec<Literal>>, SizeLimitExceeded>;
+
+/// Clause sets shared by both forms.
+///
+/// For CNF the outer operator is `∧` and clauses are disjunctions; for DNF the
+/// roles are swapped.
+struct Distributor {
+    outer_is_and: bool,
+    limit: usize,
+}
+
+impl Distributor {
+    fn too_large(&self) -> SizeLimitExceeded {
+        SizeLimitExceeded { limit: self.limit }
+    }
+
+    /// The clauses of a constant: the identity of the outer operator has no
+    /// clauses, its absorbing element is a single empty clause.
+    fn value(&self, b: bool) -> Vec<Vec<Literal>> {
+        if b == self.outer_is_and {
+            Vec::new()
+        } else {
+            vec![Vec::new()]
+        }
+    }
+
+    /// Joins two clause sets with the outer operator.
+    fn outer(&self, left: Clauses, right: Clauses) -> Clauses {
+        let (left, right) = (left?, right?);
+        if left.len() + right.len() > self.limit {
+            return Err(self.too_large());
+        }
+        let mut out = left;
+        out.extend(right);
+        Ok(simplify(out))
+    }
+
+    /// Joins two clause sets with the inner operator by distribution.
+    fn inner(&self, left: Clauses, right: Clauses) -> Clauses {
+        let (left, right) = (left?, right?);
+        if left.len().saturating_mul(right.len()) > self.limit {
+            return Err(self.too_large());
+        }
+        let mut out = Vec::with_capacity(left.len() * right.len());
+        for l in &left {
+            for r in &right {
+                out.push(l.iter().chain(r).cloned().collect());
+            }
+        }
+        Ok(simplify(out))
+    }
+
+    fn and(&self, left: Clauses, right: Clauses) -> Clauses {
+        if self.outer_is_and {
+            self.outer(left, right)
+        } else {
+            self.inner(left, right)
+        }
+    }
+
+    fn or(&self, left: Clauses, right: Clauses) -> Clauses {
+        if self.outer_is_and {
+            self.inner(left, right)
+        } else {
+            self.outer(left, right)
+        }
+    }
+
+    /// The clauses of `root`.
+    ///
+    /// Rather than converting to negation normal form first, which copies
+    /// both operands of every `⇔`, each node is converted once in each
+    /// polarity its parent needs. The size limit therefore applies before
+    /// any exponential work is done.
+    fn clauses(&self, root: &Node) -> Clauses {
+        let mut clausifier = Clausifier {
+            distributor: self,
+            polarities: Polarities::default(),
+            done: Vec::new(),
+        };
+        root.walk(&mut clausifier);
+        let [clauses, _] = clausifier.done.pop().unwrap();
+        clauses.unwrap()
+    }
+
+    /// The clauses of a binary node in the polarities in `needs`, given
+    /// those of its operands.
+    fn binary(
+        &self,
+        node: &Node,
+        [pos, neg]: Polarity,
+        [lp, ln]: [Option<Clauses>; 2],
+        [rp, rn]: [Option<Clauses>; 2],
+    ) -> [Option<Clauses>; 2] {
+        let get = |clauses: Option<Clauses>| clauses.expect("polarity was requested");
+        match node {
+            Node::And(_, _) => [
+                pos.then(|| self.and(get(lp), get(rp))),
+                neg.then(|| self.or(get(ln), get(rn))),
+            ],
+            Node::Or(_, _) => [
+                pos.then(|| self.or(get(lp), get(rp))),
+                neg.then(|| self.and(get(ln), get(rn))),
+            ],
+            // a ⇒ b  ≡  ¬a ∨ b,  ¬(a ⇒ b)  ≡  a ∧ ¬b
+            Node::If(_, _) => [
+                pos.then(|| self.or(get(ln), get(rp))),
+                neg.then(|| self.and(get(lp), get(rn))),
+            ],
+            // a ⇔ b  ≡  (a ∧ b) ∨ (¬a ∧ ¬b),  ¬(a ⇔ b)  ≡  (a ∧ ¬b) ∨ (¬a ∧ b)
+            _ => {
+                let (lp, ln, rp, rn) = (get(lp), get(ln), get(rp), get(rn));
+                let iff = pos.then(|| {
+                    let both = self.and(lp.clone(), rp.clone());
+                    self.or(both, self.and(ln.clone(), rn.clone()))
+                });
+                let xor = neg.then(|| {
+                    let left_only = self.and(lp, rn);
+                    self.or(left_only, self.and(ln, rp))
+                });
+                [iff, xor]
+            }
+        }
+    }
+}
+
+/// Converts every node into clauses, in each polarity it occurs with.
+struct Clausifier<'a, 'd> {
+    distributor: &'d Distributor,
+    polarities: Polarities<'a>,
+    /// The clauses of the converted operands, as themselves and negated
+    /// where needed.
+    done: Vec<[Option<Clauses>; 2]>,
+}
+
+impl<'a> Visitor<'a> for Clausifier<'a, '_> {
+    fn pre(&mut self, node: &'a Node) -> bool {
+        self.polarities.enter(node);
+        true
+    }
+
+    fn post(&mut self, node: &'a Node) {
+        let needs = self.polarities.exit();
+        let [pos, neg] = needs;
+        let clauses = match node {
+            Node::Atom(s) => {
+                let literal = |positive| Ok(vec![vec![Literal::new(s.clone(), positive)]]);
+                [pos.then(|| literal(true)), neg.then(|| literal(false))]
+            }
+            Node::Value(b) => [
+                pos.then(|| Ok(self.distributor.value(*b))),
+                neg.then(|| Ok(self.distributor.value(!*b))),
+            ],
+            Node::Not(_) => {
+                let [operand_pos, operand_neg] = self.done.pop().unwrap();
+                [operand_neg, operand_pos]
+            }
+            _ => {
+                let right = self.done.pop().unwrap();
+                let left = self.done.pop().unwrap();
+                self.distributor.binary(node, needs, left, right)
+            }
+        };
+        self.done.push(clauses);
+    }
+}
+
+/// Sorts and deduplicates literals, then drops tautological and subsumed
+/// clauses.
+fn simplify(clauses: Vec<Vec<Literal>>) -> Vec<Vec<Literal>> {
+    let mut clauses: Vec<_> = clauses
+        .into_iter()
+        .filter_map(|mut clause| {
+            clause.sort_unstable();
+            clause.dedup();
+            let tautology = clause
+                .windows(2)
+                .any(|w| w[0].name == w[1].name && w[0].positive != w[1].positive);
+            (!tautology).then_some(clause)
+        })
+        .collect();
+    clauses.sort_unstable_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)));
+    clauses.dedup();
+
+    let mut kept: Vec<Vec<Literal>> = Vec::with_capacity(clauses.len());
+    for clause in clauses {
+        // Shorter clauses come first, so only they can subsume this one
+        let subsumed = kept
+            .iter()
+            .any(|k| k.iter().all(|l| clause.binary_search(l).is_ok()));
+        if !subsumed {
+            kept.push(clause);
+        }
+    }
+    kept
+}
+
+/// Joins `nodes` with `∧` if `and` is set, `∨` otherwise.
+fn fold(nodes: impl Iterator<Item = Node>, and: bool) -> Node {
+    nodes
+        .reduce(|acc, n| {
+            if and {
+                Node::And(Box::new(acc), Box::new(n))
+            } else {
+                Node::Or(Box::new(acc), Box::new(n))
+            }
+        })
+        .unwrap_or(Node::Value(and))
+}
+
+fn join(clauses: &[Vec<Literal>], outer_is_and: bool) -> Node {
+    let clauses = clauses
+        .iter()
+        .map(|c| fold(c.iter().map(Literal::to_node), !outer_is_and));
+    fold(clauses, outer_is_and)
+}
+
+fn fmt_clauses(
+    f: &mut fmt::Formatter<'_>,
+    clauses: &[Vec<Literal>],
+    outer: &str,
+    inner: &str,
+    empty: &str,
+    empty_clause: &str,
+) -> fmt::Result {
+    if clauses.is_empty() {
+        return write!(f, "{}", empty);
+    }
+    for (i, clause) in clauses.iter().enumerate() {
+        if i > 0 {
+            write!(f, " {} ", outer)?;
+        }
+        if clause.is_empty() {
+            write!(f, "{}", empty_clause)?;
+            continue;
+        }
+        let parens = clauses.len() > 1 && clause.len() > 1;
+        if parens {
+            write!(f, "(")?;
+        }
+        for (j, literal) in clause.iter().enumerate() {
+            if j > 0 {
+                write!(f, " {} ", inner)?;
+            }
+            write!(f, "{}", literal)?;
+        }
+        if parens {
+            write!(f, ")")?;
+        }
+    }
+    Ok(())
+}
+
+impl Cnf {
+    pub fn to_formula(&self) -> Formula {
+        Formula::from_root(join(&self.clauses, true))
+    }
+}
+
+impl Dnf {
+    pub fn to_formula(&self) -> Formula {
+        Formula::from_root(join(&self.terms, false))
+    }
+}
+
+impl fmt::Display for Cnf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_clauses(f, &self.clauses, "∧", "∨", "T", "F")
+    }
+}
+
+impl fmt::Display for Dnf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_clauses(f, &self.terms, "∨", "∧", "F", "T")
+    }
+}
+
+impl Formula {
+    /// Converts the formula to conjunctive normal form by distribution.
+    ///
+    /// Duplicate literals and clauses, tautological clauses and subsumed
+    /// clauses are removed along the way. Distribution can grow exponentially,
+    /// so the conversion stops with an error as soon as an intermediate clause
+    /// set would hold more than `limit` clauses.
+    pub fn to_cnf(&self, limit: usize) -> Result<Cnf, SizeLimitExceeded> {
+        let distributor = Distributor {
+            outer_is_and: true,
+            limit,
+        };
+        let clauses = distributor.clauses(&self.root)?;
+        Ok(Cnf { clauses })
+    }
+
+    /// Converts the formula to disjunctive normal form by distribution.
+    ///
+    /// This is the dual of [`Formula::to_cnf`]: contradictory and absorbed
+    /// terms are removed, and `limit` bounds the number of terms.
+    pub fn to_dnf(&self, limit: usize) -> Result<Dnf, SizeLimitExceeded> {
+        let distributor = Distributor {
+            outer_is_and: false,
+            limit,
+        };
+        let terms = distributor.clauses(&self.root)?;
+        Ok(Dnf { terms })
+    }
+}
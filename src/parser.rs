@@ -17,16 +17,7 @@ impl FormulaParser {
     pub fn parse(self) -> Formula {
         let root = self.parse_expr();
         // println!("{:?}", root);
-        let variables = root
-            .children()
-            .into_iter()
-            .filter_map(|n| match n {
-                Node::Atom(s) => Some(s),
-                _ => None,
-            })
-            .cloned()
-            .collect();
-        Formula { variables, root }
+        Formula::from_root(root)
     }
 
     fn parse_expr(self) -> Node {
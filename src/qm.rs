@@ -0,0 +1,194 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    cube::{self, Cube},
+    Formula, Node,
+};
+
+/// Computes all prime implicants of the function whose on-set is `minterms`.
+pub(crate) fn prime_implicants(minterms: &[u64], num_vars: usize) -> Vec<Cube> {
+    let mut current: HashSet<Cube> = minterms
+        .iter()
+        .map(|&m| Cube::minterm(m, num_vars))
+        .collect();
+    let mut primes = Vec::new();
+
+    while !current.is_empty() {
+        let mut merged = HashSet::new();
+        let mut used = HashSet::new();
+        for &cube in &current {
+            // Combine with every cube differing in exactly one cared-for bit
+            let mut care = cube.care;
+            while care != 0 {
+                let bit = care & care.wrapping_neg();
+                care &= care - 1;
+                let partner = Cube {
+                    bits: cube.bits ^ bit,
+                    care: cube.care,
+                };
+                if current.contains(&partner) {
+                    used.insert(cube);
+                    merged.insert(Cube {
+                        bits: cube.bits & !bit,
+                        care: cube.care & !bit,
+                    });
+                }
+            }
+        }
+        primes.extend(current.iter().filter(|c| !used.contains(c)));
+        current = merged;
+    }
+    primes.sort_unstable();
+    primes
+}
+
+/// Most products Petrick's method may hold at once before [`minimum_cover`]
+/// settles for a greedy cover.
+const PETRICK_LIMIT: usize = 1_000;
+
+/// Picks a minimum set of `primes` covering `minterms`, preferring fewer
+/// literals among covers of the same size.
+///
+/// Essential primes are taken first; the remaining cyclic core is solved
+/// exactly with Petrick's method. Its expansion can grow exponentially, so
+/// once it would hold more than [`PETRICK_LIMIT`] products the core is
+/// covered greedily instead, and the cover may not be minimum.
+pub(crate) fn minimum_cover(primes: &[Cube], minterms: &[u64]) -> Vec<Cube> {
+    let covering: HashMap<u64, Vec<usize>> = minterms
+        .iter()
+        .map(|&m| {
+            let covers = (0..primes.len())
+                .filter(|&i| primes[i].contains(m))
+                .collect();
+            (m, covers)
+        })
+        .collect();
+
+    let mut selected: HashSet<usize> = covering
+        .values()
+        .filter(|covers| covers.len() == 1)
+        .map(|covers| covers[0])
+        .collect();
+    let mut remaining: Vec<&Vec<usize>> = covering
+        .values()
+        .filter(|covers| !covers.iter().any(|i| selected.contains(i)))
+        .collect();
+    remaining.sort_unstable();
+    remaining.dedup();
+
+    let core = petrick(primes, &remaining).unwrap_or_else(|| greedy(primes, &remaining));
+    selected.extend(core);
+
+    let mut cover: Vec<Cube> = selected.into_iter().map(|i| primes[i]).collect();
+    cover.sort_unstable_by(|a, b| b.cmp(a));
+    cover
+}
+
+/// Solves the cyclic core exactly: multiplies out the product of sums,
+/// absorbing any product that is a superset of another, and returns the
+/// cheapest product. Returns `None` if the expansion exceeds
+/// [`PETRICK_LIMIT`] products.
+fn petrick(primes: &[Cube], remaining: &[&Vec<usize>]) -> Option<Vec<usize>> {
+    let mut products: Vec<Vec<usize>> = vec![Vec::new()];
+    for sum in remaining {
+        let mut next: Vec<Vec<usize>> = Vec::new();
+        for product in &products {
+            if sum.iter().any(|i| product.binary_search(i).is_ok()) {
+                next.push(product.clone());
+                continue;
+            }
+            if next.len() + sum.len() > PETRICK_LIMIT {
+                return None;
+            }
+            for &i in sum.iter() {
+                let mut extended = product.clone();
+                let at = extended.binary_search(&i).unwrap_err();
+                extended.insert(at, i);
+                next.push(extended);
+            }
+        }
+        next.sort_unstable_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)));
+        next.dedup();
+        products.clear();
+        for product in next {
+            let absorbed = products
+                .iter()
+                .any(|p| p.iter().all(|i| product.binary_search(i).is_ok()));
+            if !absorbed {
+                products.push(product);
+            }
+        }
+    }
+
+    let cost = |product: &Vec<usize>| {
+        let literals: u32 = product.iter().map(|&i| primes[i].literals()).sum();
+        (product.len(), literals)
+    };
+    Some(products.into_iter().min_by_key(cost).unwrap_or_default())
+}
+
+/// Covers the cyclic core by repeatedly taking the prime that satisfies the
+/// most remaining sums, preferring fewer literals on ties.
+fn greedy(primes: &[Cube], remaining: &[&Vec<usize>]) -> Vec<usize> {
+    let mut uncovered = remaining.to_vec();
+    let mut chosen = Vec::new();
+    while !uncovered.is_empty() {
+        let mut hits: HashMap<usize, usize> = HashMap::new();
+        for sum in &uncovered {
+            for &i in sum.iter() {
+                *hits.entry(i).or_default() += 1;
+            }
+        }
+        let (best, _) = hits
+            .into_iter()
+            .max_by_key(|&(i, count)| (count, std::cmp::Reverse((primes[i].literals(), i))))
+            .expect("every minterm is covered by some prime");
+        uncovered.retain(|sum| !sum.contains(&best));
+        chosen.push(best);
+    }
+    chosen
+}
+
+impl Formula {
+    /// Returns a minimal sum of products equivalent to the formula, computed
+    /// with the Quine–McCluskey algorithm.
+    ///
+    /// The result has the fewest product terms possible, and the fewest
+    /// literals among those.
+    ///
+    /// Both steps are exponential: the truth table and the prime implicants
+    /// grow with `2^n` for `n` variables, and Petrick's method with the size
+    /// of the cyclic core. When Petrick's method would grow too large, the
+    /// core is covered greedily, so the result is still equivalent but may
+    /// have more terms than necessary. See [`Formula::minimize_heuristic`]
+    /// for larger functions.
+    pub fn minimize(&self) -> Formula {
+        self.minimize_with_dont_cares(&Formula::from_root(Node::Value(false)))
+    }
+
+    /// Like [`Formula::minimize`], but the result may take any value on the
+    /// rows where `dont_care` holds, which allows larger implicants.
+    ///
+    /// The cost is exponential in the number of variables, as for
+    /// [`Formula::minimize`].
+    pub fn minimize_with_dont_cares(&self, dont_care: &Formula) -> Formula {
+        let variables = self.variables_with(dont_care);
+        let values = self.truth_vector_over(&variables);
+        let dont_cares = dont_care.truth_vector_over(&variables);
+
+        let mut on = Vec::new();
+        let mut on_or_dc = Vec::new();
+        for (i, (value, dc)) in values.into_iter().zip(dont_cares).enumerate() {
+            if dc {
+                on_or_dc.push(i as u64);
+            } else if value {
+                on.push(i as u64);
+                on_or_dc.push(i as u64);
+            }
+        }
+        // Don't-cares help form primes but never need to be covered
+        let primes = prime_implicants(&on_or_dc, variables.len());
+        let cover = minimum_cover(&primes, &on);
+        cube::to_dnf(&cover, &variables).to_formula()
+    }
+}
@@ -0,0 +1,389 @@
+use std::collections::HashMap;
+
+use crate::{Cnf, Formula, Node, Visitor};
+
+/// A literal over a solver variable, packed as `var * 2 + negated`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct Lit(u32);
+
+impl Lit {
+    pub fn new(var: usize, positive: bool) -> Lit {
+        Lit((var as u32) << 1 | u32::from(!positive))
+    }
+
+    pub fn var(self) -> usize {
+        (self.0 >> 1) as usize
+    }
+
+    pub fn is_positive(self) -> bool {
+        self.0 & 1 == 0
+    }
+
+    fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl std::ops::Not for Lit {
+    type Output = Lit;
+
+    fn not(self) -> Lit {
+        Lit(self.0 ^ 1)
+    }
+}
+
+/// A DPLL solver with two watched literals and chronological backtracking.
+///
+/// Every call to [`Solver::solve`] starts from an empty assignment, so clauses
+/// may be added freely between calls. Decisions are made in variable order,
+/// trying `true` first, which makes the first model found the first matching
+/// row of the truth table when the formula's variables are numbered in table
+/// order.
+pub(crate) struct Solver {
+    clauses: Vec<Vec<Lit>>,
+    watches: Vec<Vec<usize>>,
+    assigns: Vec<Option<bool>>,
+    trail: Vec<Lit>,
+    // Trail index of each decision, and whether it has already been flipped
+    levels: Vec<(usize, bool)>,
+    queue_head: usize,
+    units: Vec<Lit>,
+    unsat: bool,
+}
+
+impl Solver {
+    pub fn new() -> Solver {
+        Solver {
+            clauses: Vec::new(),
+            watches: Vec::new(),
+            assigns: Vec::new(),
+            trail: Vec::new(),
+            levels: Vec::new(),
+            queue_head: 0,
+            units: Vec::new(),
+            unsat: false,
+        }
+    }
+
+    pub fn new_var(&mut self) -> usize {
+        self.assigns.push(None);
+        self.watches.push(Vec::new());
+        self.watches.push(Vec::new());
+        self.assigns.len() - 1
+    }
+
+    pub fn add_clause(&mut self, mut clause: Vec<Lit>) {
+        clause.sort_unstable_by_key(|l| l.0);
+        clause.dedup();
+        if clause.windows(2).any(|w| w[0] == !w[1]) {
+            return;
+        }
+        match clause.len() {
+            0 => self.unsat = true,
+            1 => self.units.push(clause[0]),
+            _ => {
+                let id = self.clauses.len();
+                self.watches[(!clause[0]).index()].push(id);
+                self.watches[(!clause[1]).index()].push(id);
+                self.clauses.push(clause);
+            }
+        }
+    }
+
+    fn value(&self, lit: Lit) -> Option<bool> {
+        self.assigns[lit.var()].map(|v| v == lit.is_positive())
+    }
+
+    fn enqueue(&mut self, lit: Lit) -> bool {
+        match self.value(lit) {
+            Some(v) => v,
+            None => {
+                self.assigns[lit.var()] = Some(lit.is_positive());
+                self.trail.push(lit);
+                true
+            }
+        }
+    }
+
+    /// Propagates all pending assignments, returning `false` on a conflict.
+    fn propagate(&mut self) -> bool {
+        while self.queue_head < self.trail.len() {
+            let falsified = !self.trail[self.queue_head];
+            self.queue_head += 1;
+
+            // Watch lists are keyed by the literal whose assignment makes the
+            // watched literal false.
+            let watchers = std::mem::take(&mut self.watches[(!falsified).index()]);
+            let mut kept = Vec::with_capacity(watchers.len());
+            let mut conflict = false;
+            for (i, &id) in watchers.iter().enumerate() {
+                if conflict {
+                    kept.extend_from_slice(&watchers[i..]);
+                    break;
+                }
+                let clause = &mut self.clauses[id];
+                if clause[0] == falsified {
+                    clause.swap(0, 1);
+                }
+                let other = clause[0];
+                if self.assigns[other.var()] == Some(other.is_positive()) {
+                    kept.push(id);
+                    continue;
+                }
+                let replacement = (2..clause.len()).find(|&k| {
+                    let lit = clause[k];
+                    self.assigns[lit.var()] != Some(!lit.is_positive())
+                });
+                match replacement {
+                    Some(k) => {
+                        clause.swap(1, k);
+                        let watch = clause[1];
+                        self.watches[(!watch).index()].push(id);
+                    }
+                    None => {
+                        kept.push(id);
+                        if !self.enqueue(other) {
+                            conflict = true;
+                        }
+                    }
+                }
+            }
+            self.watches[(!falsified).index()].extend(kept);
+            if conflict {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn undo_to(&mut self, len: usize) {
+        for lit in self.trail.drain(len..) {
+            self.assigns[lit.var()] = None;
+        }
+        self.queue_head = len;
+    }
+
+    /// Searches for a model, returning the value of every variable.
+    pub fn solve(&mut self) -> Option<Vec<bool>> {
+        self.undo_to(0);
+        self.levels.clear();
+        if self.unsat {
+            return None;
+        }
+        for i in 0..self.units.len() {
+            if !self.enqueue(self.units[i]) {
+                return None;
+            }
+        }
+
+        loop {
+            if !self.propagate() {
+                // Flip the most recent decision that has not been flipped yet
+                loop {
+                    let (start, flipped) = self.levels.pop()?;
+                    let decision = self.trail[start];
+                    self.undo_to(start);
+                    if !flipped {
+                        self.levels.push((start, true));
+                        self.enqueue(!decision);
+                        break;
+                    }
+                }
+                continue;
+            }
+
+            let Some(var) = self.assigns.iter().position(Option::is_none) else {
+                return Some(self.assigns.iter().map(|v| v.unwrap()).collect());
+            };
+            self.levels.push((self.trail.len(), false));
+            self.enqueue(Lit::new(var, true));
+        }
+    }
+}
+
+/// Tseitin encoding of a formula tree into solver clauses.
+///
+/// The tree is walked in post-order, so the encoding is linear in the size
+/// of the formula.
+pub(crate) struct Encoder<'a> {
+    pub solver: Solver,
+    pub atoms: HashMap<&'a str, usize>,
+    constant: Option<usize>,
+    /// Literals of the operands not yet used by their parent.
+    lits: Vec<Lit>,
+}
+
+impl<'a> Encoder<'a> {
+    /// Creates an encoder whose first variables are `names`, in order.
+    pub fn new(names: impl IntoIterator<Item = &'a str>) -> Encoder<'a> {
+        let mut solver = Solver::new();
+        let mut atoms = HashMap::new();
+        for name in names {
+            atoms.entry(name).or_insert_with(|| solver.new_var());
+        }
+        Encoder {
+            solver,
+            atoms,
+            constant: None,
+            lits: Vec::new(),
+        }
+    }
+
+    fn atom(&mut self, name: &'a str) -> Lit {
+        let solver = &mut self.solver;
+        Lit::new(
+            *self.atoms.entry(name).or_insert_with(|| solver.new_var()),
+            true,
+        )
+    }
+
+    fn constant(&mut self, value: bool) -> Lit {
+        let var = match self.constant {
+            Some(var) => var,
+            None => {
+                let var = self.solver.new_var();
+                self.solver.add_clause(vec![Lit::new(var, true)]);
+                self.constant = Some(var);
+                var
+            }
+        };
+        Lit::new(var, value)
+    }
+
+    fn gate(&mut self, clauses: impl FnOnce(Lit) -> Vec<Vec<Lit>>) -> Lit {
+        let out = Lit::new(self.solver.new_var(), true);
+        for clause in clauses(out) {
+            self.solver.add_clause(clause);
+        }
+        out
+    }
+
+    /// Returns a literal equivalent to `node`.
+    pub fn encode(&mut self, node: &'a Node) -> Lit {
+        node.walk(self);
+        self.lits.pop().unwrap()
+    }
+
+    /// Asserts that `node` holds.
+    pub fn assert(&mut self, node: &'a Node) {
+        let root = self.encode(node);
+        self.solver.add_clause(vec![root]);
+    }
+}
+
+impl<'a> Visitor<'a> for Encoder<'a> {
+    fn post(&mut self, node: &'a Node) {
+        let lit = match node {
+            Node::Atom(name) => self.atom(name),
+            Node::Value(b) => self.constant(*b),
+            Node::Not(_) => !self.lits.pop().unwrap(),
+            _ => {
+                let b = self.lits.pop().unwrap();
+                let a = self.lits.pop().unwrap();
+                match node {
+                    Node::And(_, _) => {
+                        self.gate(|x| vec![vec![!x, a], vec![!x, b], vec![x, !a, !b]])
+                    }
+                    Node::Or(_, _) => self.gate(|x| vec![vec![!x, a, b], vec![x, !a], vec![x, !b]]),
+                    Node::If(_, _) => {
+                        let a = !a;
+                        self.gate(|x| vec![vec![!x, a, b], vec![x, !a], vec![x, !b]])
+                    }
+                    _ => self.gate(|x| {
+                        vec![
+                            vec![!x, !a, b],
+                            vec![!x, a, !b],
+                            vec![x, a, b],
+                            vec![x, !a, !b],
+                        ]
+                    }),
+                }
+            }
+        };
+        self.lits.push(lit);
+    }
+}
+
+/// Lazy iterator over the satisfying assignments of a [`Formula`].
+///
+/// Each model is excluded from later searches by a blocking clause over the
+/// projected variables, so every yielded assignment is distinct.
+pub struct Models {
+    solver: Solver,
+    projection: Vec<(String, usize)>,
+    done: bool,
+}
+
+impl Models {
+    pub(crate) fn new(formula: &Formula, projection: &[&str]) -> Models {
+        let mut encoder = Encoder::new(projection.iter().copied());
+        encoder.assert(&formula.root);
+        let projection = projection
+            .iter()
+            .map(|&name| (name.to_string(), encoder.atoms[name]))
+            .collect();
+        Models {
+            solver: encoder.solver,
+            projection,
+            done: false,
+        }
+    }
+
+    /// Enumerates the models of `cnf` restricted to `projection`.
+    ///
+    /// Projected variables are numbered first so that decisions on them come
+    /// before any auxiliary variable.
+    pub(crate) fn from_cnf(cnf: &Cnf, projection: &[&str]) -> Models {
+        let mut solver = Solver::new();
+        let mut vars: HashMap<&str, usize> = HashMap::new();
+        let names = projection
+            .iter()
+            .copied()
+            .chain(cnf.clauses.iter().flatten().map(|l| l.name.as_str()));
+        for name in names {
+            vars.entry(name).or_insert_with(|| solver.new_var());
+        }
+        for clause in &cnf.clauses {
+            let clause = clause
+                .iter()
+                .map(|l| Lit::new(vars[l.name.as_str()], l.positive))
+                .collect();
+            solver.add_clause(clause);
+        }
+        let projection = projection
+            .iter()
+            .map(|&name| (name.to_string(), vars[name]))
+            .collect();
+        Models {
+            solver,
+            projection,
+            done: false,
+        }
+    }
+}
+
+impl Iterator for Models {
+    type Item = HashMap<String, bool>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let Some(model) = self.solver.solve() else {
+            self.done = true;
+            return None;
+        };
+        let blocking = self
+            .projection
+            .iter()
+            .map(|&(_, var)| Lit::new(var, !model[var]))
+            .collect();
+        self.solver.add_clause(blocking);
+        Some(
+            self.projection
+                .iter()
+                .map(|(name, var)| (name.clone(), model[*var]))
+                .collect(),
+        )
+    }
+}
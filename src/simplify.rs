@@ -0,0 +1,276 @@
+use std::fmt;
+
+use crate::{visit::take, Fold, Formula, Node, Visitor};
+
+/// A law of Boolean algebra applied by [`Formula::simplify`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Law {
+    /// `x ∧ T = x`, `x ∨ F = x`
+    Identity,
+    /// `x ∧ F = F`, `x ∨ T = T`
+    Annihilation,
+    /// `x ∧ x = x`, `x ∨ x = x`
+    Idempotence,
+    /// `x ∧ (x ∨ y) = x`, `x ∨ (x ∧ y) = x`
+    Absorption,
+    /// `x ∧ ¬x = F`, `x ∨ ¬x = T`
+    Complement,
+    /// `¬¬x = x`
+    DoubleNegation,
+    /// `¬(x ∧ y) = ¬x ∨ ¬y`, `¬(x ∨ y) = ¬x ∧ ¬y`
+    DeMorgan,
+    /// Negations, implications and equivalences of constants, e.g.
+    /// `¬T = F` or `x ⇒ F = ¬x`
+    ConstantFolding,
+}
+
+impl fmt::Display for Law {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Law::Identity => "identity",
+            Law::Annihilation => "annihilation",
+            Law::Idempotence => "idempotence",
+            Law::Absorption => "absorption",
+            Law::Complement => "complement",
+            Law::DoubleNegation => "double negation",
+            Law::DeMorgan => "De Morgan",
+            Law::ConstantFolding => "constant folding",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// One rewrite of a subterm.
+#[derive(Clone, Debug)]
+pub struct Step {
+    pub law: Law,
+    pub before: Node,
+    pub after: Node,
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} ⟶ {}", self.law, self.before, self.after)
+    }
+}
+
+fn not(node: Node) -> Node {
+    Node::Not(Box::new(node))
+}
+
+/// Whether `a` is the negation of `b`.
+fn negates(a: &Node, b: &Node) -> bool {
+    matches!(a, Node::Not(x) if **x == *b)
+}
+
+/// The operands of `node` if it is `∨` and `and` is set, or `∧` and `and` is
+/// unset.
+fn dual_operands(node: &Node, and: bool) -> Option<(&Node, &Node)> {
+    match node {
+        Node::Or(l, r) if and => Some((l, r)),
+        Node::And(l, r) if !and => Some((l, r)),
+        _ => None,
+    }
+}
+
+/// Rewrites `x ∧ y` if `and` is set, `x ∨ y` otherwise. The two cases are
+/// duals, so `T` is the identity of `∧` and the annihilator of `∨`.
+fn binary(left: &Node, right: &Node, and: bool) -> Option<(Law, Node)> {
+    let unit = Node::Value(and);
+    let zero = Node::Value(!and);
+    let absorbs = |x: &Node, other: &Node| match dual_operands(other, and) {
+        Some((a, b)) => a == x || b == x,
+        None => false,
+    };
+
+    if *left == unit {
+        Some((Law::Identity, right.clone()))
+    } else if *right == unit {
+        Some((Law::Identity, left.clone()))
+    } else if *left == zero || *right == zero {
+        Some((Law::Annihilation, zero))
+    } else if left == right {
+        Some((Law::Idempotence, left.clone()))
+    } else if negates(left, right) || negates(right, left) {
+        Some((Law::Complement, zero))
+    } else if absorbs(left, right) {
+        Some((Law::Absorption, left.clone()))
+    } else if absorbs(right, left) {
+        Some((Law::Absorption, right.clone()))
+    } else {
+        None
+    }
+}
+
+/// Applies the first law that matches at the root of `node`.
+fn rewrite(node: &Node) -> Option<(Law, Node)> {
+    match node {
+        Node::Not(operand) => match &**operand {
+            Node::Value(b) => Some((Law::ConstantFolding, Node::Value(!b))),
+            Node::Not(x) => Some((Law::DoubleNegation, *x.clone())),
+            Node::And(l, r) => Some((
+                Law::DeMorgan,
+                Node::Or(Box::new(not(*l.clone())), Box::new(not(*r.clone()))),
+            )),
+            Node::Or(l, r) => Some((
+                Law::DeMorgan,
+                Node::And(Box::new(not(*l.clone())), Box::new(not(*r.clone()))),
+            )),
+            _ => None,
+        },
+        Node::And(l, r) => binary(l, r, true),
+        Node::Or(l, r) => binary(l, r, false),
+        Node::If(l, r) => match (&**l, &**r) {
+            (Node::Value(true), x) => Some((Law::ConstantFolding, x.clone())),
+            (Node::Value(false), _) | (_, Node::Value(true)) => {
+                Some((Law::ConstantFolding, Node::Value(true)))
+            }
+            (x, Node::Value(false)) => Some((Law::ConstantFolding, not(x.clone()))),
+            _ => None,
+        },
+        Node::Iff(l, r) => match (&**l, &**r) {
+            (Node::Value(true), x) | (x, Node::Value(true)) => {
+                Some((Law::ConstantFolding, x.clone()))
+            }
+            (Node::Value(false), x) | (x, Node::Value(false)) => {
+                Some((Law::ConstantFolding, not(x.clone())))
+            }
+            _ => None,
+        },
+        Node::Atom(_) | Node::Value(_) => None,
+    }
+}
+
+/// Simplifies every node after its operands, recording every step.
+struct Simplifier<'s> {
+    steps: &'s mut Vec<Step>,
+}
+
+impl Fold for Simplifier<'_> {
+    fn fold_not(&mut self, operand: Node) -> Node {
+        negate(operand, self.steps)
+    }
+
+    fn fold_and(&mut self, left: Node, right: Node) -> Node {
+        settle(Node::and(left, right), self.steps)
+    }
+
+    fn fold_or(&mut self, left: Node, right: Node) -> Node {
+        settle(Node::or(left, right), self.steps)
+    }
+
+    fn fold_if(&mut self, left: Node, right: Node) -> Node {
+        settle(Node::implies(left, right), self.steps)
+    }
+
+    fn fold_iff(&mut self, left: Node, right: Node) -> Node {
+        settle(Node::iff(left, right), self.steps)
+    }
+}
+
+/// Rewrites the root of `node`, whose operands are simplified.
+///
+/// Every law except De Morgan yields a simplified subterm, a constant or the
+/// negation of a simplified subterm, so at most that negation needs more work.
+fn settle(node: Node, steps: &mut Vec<Step>) -> Node {
+    let Some((law, mut after)) = rewrite(&node) else {
+        return node;
+    };
+    steps.push(Step {
+        law,
+        before: node,
+        after: after.clone(),
+    });
+    match &mut after {
+        Node::Not(operand) => negate(take(operand), steps),
+        _ => after,
+    }
+}
+
+/// Simplifies `¬operand`, where `operand` is simplified.
+fn negate(operand: Node, steps: &mut Vec<Step>) -> Node {
+    match operand {
+        Node::And(_, _) | Node::Or(_, _) => {
+            let mut negation = Negation {
+                steps,
+                built: Vec::new(),
+            };
+            operand.walk(&mut negation);
+            negation.built.pop().unwrap()
+        }
+        operand => negate_leaf(operand, steps),
+    }
+}
+
+/// Simplifies `¬operand` for an `operand` that De Morgan does not apply to.
+fn negate_leaf(operand: Node, steps: &mut Vec<Step>) -> Node {
+    let node = not(operand);
+    match rewrite(&node) {
+        Some((law, after)) => {
+            steps.push(Step {
+                law,
+                before: node,
+                after: after.clone(),
+            });
+            after
+        }
+        None => node,
+    }
+}
+
+/// Pushes the negation of a simplified formula through its `∧` and `∨`
+/// nodes with De Morgan, then simplifies the dual nodes bottom-up.
+struct Negation<'s> {
+    steps: &'s mut Vec<Step>,
+    built: Vec<Node>,
+}
+
+impl<'a> Visitor<'a> for Negation<'_> {
+    fn pre(&mut self, node: &'a Node) -> bool {
+        let after = match node {
+            Node::And(l, r) => Node::or(not((**l).clone()), not((**r).clone())),
+            Node::Or(l, r) => Node::and(not((**l).clone()), not((**r).clone())),
+            _ => {
+                let negated = negate_leaf(node.clone(), self.steps);
+                self.built.push(negated);
+                return false;
+            }
+        };
+        self.steps.push(Step {
+            law: Law::DeMorgan,
+            before: not(node.clone()),
+            after,
+        });
+        true
+    }
+
+    fn post(&mut self, node: &'a Node) {
+        let right = self.built.pop().unwrap();
+        let left = self.built.pop().unwrap();
+        let dual = match node {
+            Node::And(_, _) => Node::or(left, right),
+            _ => Node::and(left, right),
+        };
+        let settled = settle(dual, self.steps);
+        self.built.push(settled);
+    }
+}
+
+impl Formula {
+    /// Simplifies the formula with the laws listed in [`Law`] until none
+    /// applies. The result is equivalent but not necessarily minimal.
+    pub fn simplify(&self) -> Formula {
+        self.simplify_with_steps().0
+    }
+
+    /// Like [`Formula::simplify`], also returning the rewrites in the order
+    /// they were applied, innermost subterms first.
+    pub fn simplify_with_steps(&self) -> (Formula, Vec<Step>) {
+        let mut steps = Vec::new();
+        let root = self
+            .root
+            .clone()
+            .fold(&mut Simplifier { steps: &mut steps });
+        (Formula::from_root(root), steps)
+    }
+}
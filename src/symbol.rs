@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+
+use crate::{compiled::CompiledFormula, BddManager, Formula};
+
+/// An interned variable name, valid for the [`SymbolTable`] that created it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    /// The dense index of the symbol: symbols are numbered from 0 in the
+    /// order they were interned.
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// Interns variable names so that formulas sharing a table refer to the same
+/// variable by the same [`Symbol`].
+///
+/// The table is a side index built on demand with [`Formula::intern`]: atoms
+/// still own their names and every formula keeps its own
+/// [`variables`](Formula::variables), so interning does not reduce memory
+/// use. It gives formulas that are evaluated together, e.g. in one truth
+/// table or one [`BddManager`], a shared and stable numbering of their
+/// variables.
+#[derive(Clone, Debug, Default)]
+pub struct SymbolTable {
+    names: Vec<String>,
+    symbols: HashMap<String, Symbol>,
+}
+
+impl SymbolTable {
+    pub fn new() -> SymbolTable {
+        SymbolTable::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Returns the symbol for `name`, adding it if it is new.
+    pub fn intern(&mut self, name: &str) -> Symbol {
+        if let Some(&symbol) = self.symbols.get(name) {
+            return symbol;
+        }
+        let symbol = Symbol(self.names.len() as u32);
+        self.names.push(name.to_string());
+        self.symbols.insert(name.to_string(), symbol);
+        symbol
+    }
+
+    /// Looks up `name` without interning it.
+    pub fn get(&self, name: &str) -> Option<Symbol> {
+        self.symbols.get(name).copied()
+    }
+
+    pub fn name(&self, symbol: Symbol) -> &str {
+        &self.names[symbol.index()]
+    }
+
+    /// The names in symbol order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.names.iter().map(String::as_str)
+    }
+
+    /// Every symbol, ordered by name as in a truth table.
+    pub fn sorted(&self) -> Vec<Symbol> {
+        let mut symbols: Vec<_> = self.symbols.values().copied().collect();
+        symbols.sort_unstable_by_key(|&s| self.name(s));
+        symbols
+    }
+}
+
+impl CompiledFormula {
+    /// Compiles `formula` so that variable index `i` is the symbol with index
+    /// `i` in `symbols`, letting formulas compiled against one table share
+    /// their value slices. Returns `None` if a variable is not interned.
+    pub fn with_symbols(formula: &Formula, symbols: &SymbolTable) -> Option<CompiledFormula> {
+        let variables = symbols.names().map(str::to_string).collect();
+        CompiledFormula::compile(formula, |name| Some(symbols.get(name)?.0), variables)
+    }
+
+    /// Compiles `formula` so that variable index `i` is `order[i]`, e.g. over
+    /// [`SymbolTable::sorted`] to number rows as in a truth table. Returns
+    /// `None` if a variable is not among `order`.
+    pub fn with_symbol_order(
+        formula: &Formula,
+        symbols: &SymbolTable,
+        order: &[Symbol],
+    ) -> Option<CompiledFormula> {
+        // Variable index of each symbol, by symbol index
+        let mut slots = vec![None; symbols.len()];
+        for (i, symbol) in order.iter().enumerate() {
+            slots[symbol.index()] = Some(i as u32);
+        }
+        let variables = order.iter().map(|&s| symbols.name(s).to_string()).collect();
+        CompiledFormula::compile(formula, |name| slots[symbols.get(name)?.index()], variables)
+    }
+}
+
+impl BddManager {
+    /// Creates a manager whose variable `i` is the symbol with index `i`,
+    /// ordered top to bottom by index.
+    pub fn with_symbols(symbols: &SymbolTable) -> BddManager {
+        BddManager::with_order(symbols.names())
+    }
+}
+
+impl Formula {
+    /// Interns the variables of the formula in truth table order, returning
+    /// their symbols.
+    pub fn intern(&self, symbols: &mut SymbolTable) -> Vec<Symbol> {
+        self.sorted_variables()
+            .into_iter()
+            .map(|var| symbols.intern(var))
+            .collect()
+    }
+}
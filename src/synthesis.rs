@@ -0,0 +1,306 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+use crate::{
+    cube::{self, Cube},
+    lexer::{shunting_yard, Token},
+    Cnf, Formula, Literal,
+};
+
+/// Errors in a truth vector string.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TruthVectorError {
+    /// The length is not a power of two.
+    InvalidLength(usize),
+    /// A character other than `0` or `1`.
+    InvalidCharacter(char),
+}
+
+impl fmt::Display for TruthVectorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TruthVectorError::InvalidLength(len) => {
+                write!(f, "truth vector length {} is not a power of two", len)
+            }
+            TruthVectorError::InvalidCharacter(c) => {
+                write!(f, "invalid character `{}` in truth vector", c)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TruthVectorError {}
+
+/// Returned when a minterm or maxterm index does not fit in `2^n` for `n`
+/// variables.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TermOutOfRange {
+    pub index: usize,
+    pub variables: usize,
+}
+
+impl fmt::Display for TermOutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "term {} is out of range for {} variables",
+            self.index, self.variables
+        )
+    }
+}
+
+impl std::error::Error for TermOutOfRange {}
+
+/// Checks that every index is a row of a table over `variables` variables.
+fn check_terms(terms: &[usize], variables: usize) -> Result<(), TermOutOfRange> {
+    let fits = |index: usize| variables >= usize::BITS as usize || index >> variables == 0;
+    match terms.iter().find(|&&index| !fits(index)) {
+        Some(&index) => Err(TermOutOfRange { index, variables }),
+        None => Ok(()),
+    }
+}
+
+/// Errors found while reading a CSV truth table. Line numbers are 1-based.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CsvError {
+    /// There is no header, or it has no input column.
+    MissingHeader,
+    /// A row has a different number of columns than the header.
+    ColumnCount {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// A cell is not a truth value.
+    InvalidValue { line: usize, value: String },
+    /// Two rows give different outputs for the same inputs.
+    ConflictingRow { line: usize },
+    /// More input columns than bits in a row number.
+    TooManyInputs(usize),
+    /// Two input columns have the same name.
+    DuplicateName(String),
+    /// An input column name does not parse back as that variable, e.g. `T`,
+    /// which the parser reads as a constant.
+    InvalidName(String),
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CsvError::MissingHeader => write!(f, "missing header with input columns"),
+            CsvError::ColumnCount {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {}: expected {} columns, found {}",
+                line, expected, found
+            ),
+            CsvError::InvalidValue { line, value } => {
+                write!(f, "line {}: invalid truth value `{}`", line, value)
+            }
+            CsvError::ConflictingRow { line } => {
+                write!(f, "line {}: conflicts with an earlier row", line)
+            }
+            CsvError::TooManyInputs(count) => write!(
+                f,
+                "{} input columns exceed the limit of {}",
+                count,
+                usize::BITS
+            ),
+            CsvError::DuplicateName(name) => write!(f, "duplicate column `{}`", name),
+            CsvError::InvalidName(name) => {
+                write!(f, "column name `{}` is not a variable name", name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CsvError {}
+
+/// A partially specified function read from a truth table.
+#[derive(Clone, Debug)]
+pub struct Specification {
+    /// Canonical sum of products of the rows whose output is true.
+    pub formula: Formula,
+    /// Canonical sum of products of the rows whose output is a don't-care.
+    pub dont_care: Formula,
+}
+
+/// Parses a cell: `Some(value)` for a truth value, `None` for a don't-care.
+fn parse_value(cell: &str) -> Result<Option<bool>, ()> {
+    match cell.to_ascii_lowercase().as_str() {
+        "t" | "true" | "1" => Ok(Some(true)),
+        "f" | "false" | "0" => Ok(Some(false)),
+        "x" | "-" => Ok(None),
+        _ => Err(()),
+    }
+}
+
+impl Specification {
+    /// Reads a truth table in CSV format, as the inverse of the table
+    /// rendering.
+    ///
+    /// The header names the input variables followed by one output column,
+    /// whose name is ignored. Input names must be distinct variable names
+    /// the parser reads back, so e.g. `T` is rejected. Cells hold `T`/`F`, `1`/`0` or `true`/`false`;
+    /// outputs may also be `X` or `-` for don't-cares. Rows may come in any
+    /// order, and missing rows are false.
+    pub fn from_csv(source: &str) -> Result<Specification, CsvError> {
+        let mut lines = source
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty());
+        let (_, header) = lines.next().ok_or(CsvError::MissingHeader)?;
+        let header: Vec<&str> = header.split(',').map(str::trim).collect();
+        if header.len() < 2 {
+            return Err(CsvError::MissingHeader);
+        }
+        let variables = &header[..header.len() - 1];
+        // Rows are numbered by `usize`, with one bit per input
+        if variables.len() > usize::BITS as usize {
+            return Err(CsvError::TooManyInputs(variables.len()));
+        }
+        let mut seen = HashSet::new();
+        for &name in variables {
+            if shunting_yard(name) != [Token::Atom(name.to_string())] {
+                return Err(CsvError::InvalidName(name.to_string()));
+            }
+            if !seen.insert(name) {
+                return Err(CsvError::DuplicateName(name.to_string()));
+            }
+        }
+
+        let mut rows: HashMap<usize, Option<bool>> = HashMap::new();
+        for (line, text) in lines {
+            let cells: Vec<&str> = text.split(',').map(str::trim).collect();
+            if cells.len() != header.len() {
+                return Err(CsvError::ColumnCount {
+                    line,
+                    expected: header.len(),
+                    found: cells.len(),
+                });
+            }
+            let mut values = Vec::with_capacity(cells.len());
+            for (i, cell) in cells.iter().enumerate() {
+                let value = parse_value(cell).map_err(|_| CsvError::InvalidValue {
+                    line,
+                    value: cell.to_string(),
+                })?;
+                // Only the output column may be a don't-care
+                if value.is_none() && i + 1 < cells.len() {
+                    return Err(CsvError::InvalidValue {
+                        line,
+                        value: cell.to_string(),
+                    });
+                }
+                values.push(value);
+            }
+            let output = values.pop().unwrap();
+            let row = values
+                .iter()
+                .fold(0, |acc, v| acc << 1 | usize::from(v.unwrap()));
+            if rows.insert(row, output).is_some_and(|old| old != output) {
+                return Err(CsvError::ConflictingRow { line });
+            }
+        }
+
+        let select = |wanted: Option<bool>| {
+            let mut selected: Vec<usize> = rows
+                .iter()
+                .filter(|&(_, &output)| output == wanted)
+                .map(|(&row, _)| row)
+                .collect();
+            selected.sort_unstable();
+            Formula::from_minterms(variables, &selected).expect("rows are read from the columns")
+        };
+        Ok(Specification {
+            formula: select(Some(true)),
+            dont_care: select(None),
+        })
+    }
+}
+
+/// Default variable names for [`Formula::from_truth_vector`]: the letters of
+/// the alphabet, skipping `f` and `t`, which the parser reads as constants,
+/// then pairs of letters.
+fn default_names(count: usize) -> Vec<String> {
+    let letters: Vec<char> = ('a'..='z').filter(|&c| c != 'f' && c != 't').collect();
+    let pairs = letters
+        .iter()
+        .flat_map(|&a| letters.iter().map(move |&b| format!("{}{}", a, b)));
+    letters
+        .iter()
+        .map(|c| c.to_string())
+        .chain(pairs)
+        .take(count)
+        .collect()
+}
+
+impl Formula {
+    /// Builds the canonical sum of products that is true exactly on the
+    /// given minterms.
+    ///
+    /// Minterm `i` sets variable `j` to bit `n - 1 - j` of `i`, as in
+    /// [`Formula::truth_vector`]. Returns an error if an index is `2^n` or
+    /// more.
+    pub fn from_minterms(
+        variables: &[&str],
+        minterms: &[usize],
+    ) -> Result<Formula, TermOutOfRange> {
+        check_terms(minterms, variables.len())?;
+        let cubes: Vec<_> = minterms
+            .iter()
+            .map(|&m| Cube::minterm(m as u64, variables.len()))
+            .collect();
+        Ok(cube::to_dnf(&cubes, variables).to_formula())
+    }
+
+    /// Builds the canonical product of sums that is false exactly on the
+    /// given maxterms, numbered like minterms.
+    pub fn from_maxterms(
+        variables: &[&str],
+        maxterms: &[usize],
+    ) -> Result<Formula, TermOutOfRange> {
+        check_terms(maxterms, variables.len())?;
+        let n = variables.len();
+        let clauses = maxterms
+            .iter()
+            .map(|&m| {
+                variables
+                    .iter()
+                    .enumerate()
+                    .map(|(j, var)| Literal::new(*var, m >> (n - 1 - j) & 1 == 0))
+                    .collect()
+            })
+            .collect();
+        Ok(Cnf { clauses }.to_formula())
+    }
+
+    /// Builds the canonical sum of products of a truth vector such as
+    /// `"01101001"`, where character `i` is the output for minterm `i`.
+    ///
+    /// The variables are named `a`, `b`, `c`, … in order, skipping `f` and
+    /// `t`, and continue with `aa`, `ab`, … past `z`.
+    pub fn from_truth_vector(vector: &str) -> Result<Formula, TruthVectorError> {
+        let len = vector.chars().count();
+        if !len.is_power_of_two() {
+            return Err(TruthVectorError::InvalidLength(len));
+        }
+        let mut minterms = Vec::new();
+        for (i, c) in vector.chars().enumerate() {
+            match c {
+                '1' => minterms.push(i),
+                '0' => {}
+                c => return Err(TruthVectorError::InvalidCharacter(c)),
+            }
+        }
+        let names = default_names(len.trailing_zeros() as usize);
+        let variables: Vec<_> = names.iter().map(String::as_str).collect();
+        Ok(Formula::from_minterms(&variables, &minterms).expect("minterms index the vector"))
+    }
+}
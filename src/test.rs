@@ -54,8 +54,8 @@ fn test_formula_parsing() {
         ["a", "b", "c"].into_iter().map(String::from).collect()
     );
 
-    // We can't directly compare the AST structure, so we'll test it indirectly
-    // through evaluation
+    let (a, b, c) = (Node::atom("a"), Node::atom("b"), Node::atom("c"));
+    assert_eq!(*formula.root(), Node::or(Node::and(a, b), c));
 }
 
 // Test formula evaluation
@@ -79,6 +79,29 @@ fn test_formula_evaluation() {
     assert_eq!(formula.eval(&vars), Some(false));
 }
 
+// Test evaluation short-circuits, so only variables it reaches must be set
+#[test]
+fn test_evaluation_short_circuit() {
+    let eval = |source: &str, a: bool| {
+        let vars = HashMap::from([("a".to_string(), a)]);
+        Formula::from(source).eval(&vars)
+    };
+
+    assert_eq!(eval("a & b", false), Some(false));
+    assert_eq!(eval("a & b", true), None);
+    assert_eq!(eval("a | b", true), Some(true));
+    assert_eq!(eval("a | b", false), None);
+    assert_eq!(eval("a -> b", false), Some(true));
+    assert_eq!(eval("a -> b", true), None);
+    assert_eq!(eval("a & (b | ~c)", false), Some(false));
+    assert_eq!(eval("(a & b) | ~a", false), Some(true));
+    assert_eq!(eval("~(a -> b) -> (b <-> c)", false), Some(true));
+    // The left operand is always evaluated, and `⇔` needs both
+    assert_eq!(eval("b & a", false), None);
+    assert_eq!(eval("~b | a", true), None);
+    assert_eq!(eval("a <-> b", true), None);
+}
+
 // Test operator precedence
 #[test]
 fn test_operator_precedence() {
@@ -167,3 +190,1141 @@ fn test_operator_associativity() {
 
     assert_eq!(formula.eval(&vars), Some(false));
 }
+
+// Test model enumeration against the truth table
+#[test]
+fn test_models() {
+    let formula = Formula::from("a & b | ~c");
+    let models: Vec<_> = formula.models().collect();
+    assert_eq!(models.len(), 5);
+    for model in &models {
+        assert_eq!(formula.eval(model), Some(true));
+    }
+    // Models are produced in truth table order
+    assert!(models[0]["a"] && models[0]["b"] && models[0]["c"]);
+
+    assert_eq!(Formula::from("a & ~a").models().count(), 0);
+    assert_eq!(Formula::from("T").models().count(), 1);
+
+    // The encoding is linear and needs no recursion
+    let chain = Formula::from(("a & ".repeat(100_000) + "~b").as_str());
+    let models: Vec<_> = chain.models().collect();
+    assert_eq!(models.len(), 1);
+    assert!(models[0]["a"] && !models[0]["b"]);
+}
+
+// Test projected model enumeration yields distinct assignments
+#[test]
+fn test_models_projected() {
+    let formula = Formula::from("a & b | ~c");
+    let models: Vec<_> = formula.models_projected(&["a", "c"]).collect();
+    assert_eq!(models.len(), 3);
+    let distinct: HashSet<Vec<(String, bool)>> = models
+        .iter()
+        .map(|m| {
+            let mut m: Vec<_> = m.clone().into_iter().collect();
+            m.sort();
+            m
+        })
+        .collect();
+    assert_eq!(distinct.len(), 3);
+    assert!(!models.iter().any(|m| !m["a"] && m["c"]));
+}
+
+// Small xorshift generator so the randomized tests stay reproducible
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+}
+
+fn random_node(rng: &mut Rng, depth: u32) -> Node {
+    if depth == 0 || rng.below(4) == 0 {
+        return match rng.below(10) {
+            0 => Node::Value(rng.below(2) == 0),
+            n => Node::Atom(["a", "b", "c", "d"][n as usize % 4].to_string()),
+        };
+    }
+    let op = rng.below(5);
+    let mut child = || Box::new(random_node(rng, depth - 1));
+    match op {
+        0 => Node::And(child(), child()),
+        1 => Node::Or(child(), child()),
+        2 => Node::Not(child()),
+        3 => Node::If(child(), child()),
+        _ => Node::Iff(child(), child()),
+    }
+}
+
+fn random_formulas(seed: u64, count: usize) -> Vec<Formula> {
+    let mut rng = Rng(seed);
+    (0..count)
+        .map(|_| Formula::from_root(random_node(&mut rng, 5)))
+        .collect()
+}
+
+// A function of `num_vars` variables with each row set by a coin flip
+fn random_function(seed: u64, num_vars: u32) -> Formula {
+    let mut rng = Rng(seed);
+    let vector: String = (0..1 << num_vars)
+        .map(|_| if rng.below(2) == 0 { '0' } else { '1' })
+        .collect();
+    Formula::from_truth_vector(&vector).unwrap()
+}
+
+// Compares two formulas on every assignment of the variables of `a` and `b`
+fn assert_equivalent(a: &Formula, b: &Formula) {
+    let mut variables: Vec<_> = a.variables.union(&b.variables).cloned().collect();
+    variables.sort_unstable();
+    for row in 0..1u32 << variables.len() {
+        let vars = variables
+            .iter()
+            .enumerate()
+            .map(|(j, var)| (var.clone(), row >> j & 1 == 1))
+            .collect();
+        assert_eq!(
+            a.eval(&vars),
+            b.eval(&vars),
+            "{} is not equivalent to {}",
+            a,
+            b
+        );
+    }
+}
+
+// Test negation normal form only negates atoms and preserves the truth table
+#[test]
+fn test_nnf() {
+    let nnf = Formula::from("~(a & b) -> (c <-> d)").to_nnf();
+    assert!(!nnf.to_string().contains('⇒') && !nnf.to_string().contains('⇔'));
+    assert_eq!(Formula::from("~(a & ~b)").to_nnf().to_string(), "¬a ∨ b");
+    assert_eq!(Formula::from("a | ~F").to_nnf().to_string(), "T");
+
+    for formula in random_formulas(0x2545_f491_4f6c_dd1d, 200) {
+        let nnf = formula.to_nnf();
+        for node in nnf.root.children().into_iter().chain([&nnf.root]) {
+            match node {
+                Node::Not(operand) => assert!(matches!(**operand, Node::Atom(_))),
+                Node::If(..) | Node::Iff(..) => panic!("{} is not in NNF", nnf),
+                Node::Value(_) => assert!(matches!(nnf.root, Node::Value(_))),
+                _ => {}
+            }
+        }
+        assert_equivalent(&formula, &nnf);
+    }
+}
+
+// Test clausal forms are simplified and preserve the truth table
+#[test]
+fn test_cnf_dnf() {
+    let formula = Formula::from("(a | b) & (a | b | c) & (c | ~c)");
+    assert_eq!(formula.to_cnf(100).unwrap().to_string(), "a ∨ b");
+    assert_eq!(
+        Formula::from("a -> b").to_dnf(100).unwrap().to_string(),
+        "¬a ∨ b"
+    );
+    assert_eq!(
+        Formula::from("(a | b) & c")
+            .to_dnf(100)
+            .unwrap()
+            .to_string(),
+        "(a ∧ c) ∨ (b ∧ c)"
+    );
+    assert_eq!(Formula::from("a & ~a").to_dnf(100).unwrap().terms.len(), 0);
+
+    for formula in random_formulas(0x9e37_79b9_7f4a_7c15, 200) {
+        assert_equivalent(&formula, &formula.to_cnf(1 << 16).unwrap().to_formula());
+        assert_equivalent(&formula, &formula.to_dnf(1 << 16).unwrap().to_formula());
+    }
+}
+
+// Test distribution stops at the size limit instead of blowing up
+#[test]
+fn test_cnf_size_limit() {
+    let source = "xyzwuvpqrsmn"
+        .chars()
+        .map(|c| format!("({} & {})", c, c.to_ascii_uppercase()))
+        .collect::<Vec<_>>()
+        .join(" | ");
+    let formula = Formula::from(source.as_str());
+    assert_eq!(formula.to_cnf(1000), Err(SizeLimitExceeded { limit: 1000 }));
+    assert_eq!(formula.to_dnf(1000).unwrap().terms.len(), 12);
+
+    // Biconditionals count against the limit without being expanded first
+    let parity = (1..60)
+        .map(|i| Node::atom(format!("x{}", i)))
+        .fold(Node::atom("x0"), Node::iff);
+    let parity = Formula::from(parity);
+    assert_eq!(parity.to_cnf(10), Err(SizeLimitExceeded { limit: 10 }));
+    assert_eq!(parity.to_dnf(10), Err(SizeLimitExceeded { limit: 10 }));
+    let small = Formula::from("a <-> b <-> c");
+    assert_eq!(small.to_cnf(16).unwrap().clauses.len(), 4);
+}
+
+// Test Tseitin encodings are equisatisfiable and project back onto the formula
+#[test]
+fn test_tseitin() {
+    let formula = Formula::from("(a | b) & ~c -> d <-> e");
+    let tseitin = formula.to_tseitin();
+    assert!(tseitin.cnf.clauses.len() <= 4 * 4 + 1);
+    assert_eq!(tseitin.auxiliary.len(), 4);
+    let root = &tseitin.cnf.clauses.last().unwrap()[0].name;
+    assert_eq!(
+        tseitin.definition(root).unwrap().to_string(),
+        formula.to_string()
+    );
+    assert!(tseitin.definition("a").is_none());
+
+    for formula in random_formulas(0x0123_4567_89ab_cdef, 100) {
+        let expected = formula.models().count();
+        for encoding in [formula.to_tseitin(), formula.to_plaisted_greenbaum()] {
+            let models: Vec<_> = encoding.models().collect();
+            assert_eq!(models.len(), expected, "{}", formula);
+            for model in &models {
+                assert_eq!(formula.eval(model), Some(true));
+            }
+        }
+    }
+
+    // Definitions are shared handles, so long chains encode in linear time
+    let chain = Formula::from(("a & ".repeat(100_000) + "b").as_str());
+    let tseitin = chain.to_plaisted_greenbaum();
+    assert_eq!(tseitin.auxiliary.len(), 100_000);
+    assert_eq!(tseitin.dag.len(), 100_002);
+    assert_eq!(tseitin.models().count(), 1);
+
+    // A projected name outside the formula stays free, even if it looks like
+    // an auxiliary variable
+    let formula = Formula::from("a & b");
+    assert_eq!(formula.models_projected(&["a", "_t0"]).count(), 2);
+    assert_eq!(formula.models_projected(&["_t1", "_t2"]).count(), 4);
+}
+
+// Test DIMACS export and import round-trip through the atom name comments
+#[test]
+fn test_dimacs_round_trip() {
+    let formula = Formula::from("(a | ~b) & (b | c) & ~a");
+    let cnf = formula.to_cnf(100).unwrap();
+    let dimacs = cnf.to_dimacs();
+    assert!(dimacs.contains("p cnf 3 3"));
+    assert_eq!(Cnf::from_dimacs(&dimacs), Ok(cnf));
+
+    let tseitin = Formula::from("a <-> b");
+    let dimacs = tseitin.to_tseitin().to_dimacs();
+    assert!(dimacs.starts_with("c var 1 a\nc var 2 b\n"));
+    let read = Formula::from_dimacs(&dimacs).unwrap();
+    assert_eq!(read.models_projected(&["a", "b"]).count(), 2);
+
+    let plain = Formula::from_dimacs("c example\np cnf 2 2\n1 -2 0\n2 0\n").unwrap();
+    assert_eq!(plain.to_string(), "(x1 ∨ ¬x2) ∧ x2");
+
+    // Ordinary comments that happen to start with a number name nothing
+    let plain = Formula::from_dimacs("c 2 clauses\np cnf 2 2\n1 -2 0\n2 0\n").unwrap();
+    assert_eq!(plain.to_string(), "(x1 ∨ ¬x2) ∧ x2");
+}
+
+// Test malformed DIMACS input is reported with its line
+#[test]
+fn test_dimacs_errors() {
+    assert_eq!(Cnf::from_dimacs("1 2 0\n"), Err(DimacsError::MissingHeader));
+    assert!(matches!(
+        Cnf::from_dimacs("c\np dnf 2 1\n1 0\n"),
+        Err(DimacsError::InvalidHeader { line: 2, .. })
+    ));
+    assert!(matches!(
+        Cnf::from_dimacs("p cnf 2\n1 0\n"),
+        Err(DimacsError::InvalidHeader { line: 1, .. })
+    ));
+    assert_eq!(
+        Cnf::from_dimacs("p cnf 2 1\n1 3 0\n"),
+        Err(DimacsError::VariableOutOfRange {
+            line: 2,
+            variable: 3,
+            declared: 2
+        })
+    );
+    assert_eq!(
+        Cnf::from_dimacs("p cnf 2 2\n1 -2 0\n"),
+        Err(DimacsError::ClauseCountMismatch {
+            declared: 2,
+            found: 1
+        })
+    );
+    assert!(matches!(
+        Cnf::from_dimacs("p cnf 2 1\n1 b 0\n"),
+        Err(DimacsError::InvalidLiteral { line: 2, .. })
+    ));
+    assert_eq!(
+        Cnf::from_dimacs("c var 1 a\nc var 2 a\np cnf 2 1\n1 2 0\n"),
+        Err(DimacsError::DuplicateName {
+            line: 2,
+            name: "a".to_string()
+        })
+    );
+    assert_eq!(
+        Cnf::from_dimacs("c var 2 x1\np cnf 2 1\n1 2 0\n"),
+        Err(DimacsError::DuplicateName {
+            line: 3,
+            name: "x1".to_string()
+        })
+    );
+}
+
+// Test BDDs are canonical and agree with the truth table
+#[test]
+fn test_bdd() {
+    let mut manager = BddManager::new();
+    let f = Formula::from("a -> b").to_bdd(&mut manager);
+    let g = Formula::from("~b -> ~a").to_bdd(&mut manager);
+    let h = Formula::from("~a | b | (c & ~c)").to_bdd(&mut manager);
+    assert_eq!(f, g);
+    assert_eq!(f, h);
+    assert_eq!(manager.order(), ["a", "b", "c"]);
+    assert_eq!(manager.sat_count(f), Some(6));
+
+    let tautology = Formula::from("a | ~a").to_bdd(&mut manager);
+    assert_eq!(tautology, Bdd::TRUE);
+
+    for formula in random_formulas(0x5851_f42d_4c95_7f2d, 100) {
+        let mut manager = BddManager::with_order(["d", "c", "b", "a"]);
+        let f = formula.to_bdd(&mut manager);
+        let models = formula.models_projected(&["a", "b", "c", "d"]).count();
+        assert_eq!(manager.sat_count(f), Some(models as u128), "{}", formula);
+        if let Some(model) = manager.any_model(f) {
+            assert_eq!(formula.eval(&model), Some(true));
+        }
+        let covered: u128 = manager.cubes(f).iter().map(|c| 1 << (4 - c.len())).sum();
+        assert_eq!(covered, models as u128);
+    }
+
+    // 2^128 assignments do not fit in a u128, but half of them do
+    let names: Vec<_> = (0..128).map(|i| format!("v{}", i)).collect();
+    let mut manager = BddManager::with_order(&names);
+    assert_eq!(manager.sat_count(Bdd::TRUE), None);
+    let v = manager.var("v0");
+    assert_eq!(manager.sat_count(v), Some(1 << 127));
+    let v = manager.var("v127");
+    assert_eq!(manager.sat_count(v), Some(1 << 127));
+}
+
+// Test restriction and quantification
+#[test]
+fn test_bdd_quantification() {
+    let mut manager = BddManager::new();
+    let f = Formula::from("(a & b) | (~a & c)").to_bdd(&mut manager);
+    let b = manager.var("b");
+    let c = manager.var("c");
+
+    assert_eq!(manager.restrict(f, "a", true), b);
+    assert_eq!(manager.restrict(f, "a", false), c);
+    let either = manager.apply(BddOp::Or, b, c);
+    let both = manager.apply(BddOp::And, b, c);
+    assert_eq!(manager.exists(f, &["a"]), either);
+    assert_eq!(manager.forall(f, &["a"]), both);
+    assert_eq!(manager.exists(f, &["a", "b", "c"]), Bdd::TRUE);
+}
+
+// Test reordering shrinks a badly ordered diagram without changing it
+#[test]
+fn test_bdd_reorder() {
+    let formula = Formula::from("(a & x) | (b & y) | (c & z) | (d & w)");
+    let bad_order = ["a", "b", "c", "d", "x", "y", "z", "w"];
+    let good = {
+        let mut manager = BddManager::with_order(["a", "x", "b", "y", "c", "z", "d", "w"]);
+        let f = formula.to_bdd(&mut manager);
+        manager.node_count(f)
+    };
+
+    for method in [Reordering::Sifting, Reordering::Window3] {
+        let mut manager = BddManager::with_order(bad_order);
+        let f = formula.to_bdd(&mut manager);
+        let count = manager.sat_count(f);
+        let before = manager.node_count(f);
+        let report = manager.reorder(&[f], method);
+        assert_eq!(report.before, before);
+        assert!(report.after < report.before, "{:?}", report);
+        assert_eq!(report.after, manager.node_count(f));
+        assert_eq!(manager.sat_count(f), count);
+        assert_eq!(formula.to_bdd(&mut manager), f);
+        if method == Reordering::Sifting {
+            assert_eq!(report.after, good);
+        }
+    }
+
+    let mut manager = BddManager::with_order(bad_order);
+    manager.set_auto_reorder(Some(16));
+    let f = formula.to_bdd(&mut manager);
+    assert_eq!(manager.reorder_reports().len(), 1);
+    assert_eq!(manager.node_count(f), good);
+}
+
+// Test BDD DOT export labels nodes and distinguishes edge kinds
+#[test]
+fn test_bdd_dot() {
+    let mut manager = BddManager::new();
+    let f = Formula::from("a & ~b").to_bdd(&mut manager);
+    let dot = manager.to_dot(&[("a & \"b\"", f)]);
+    assert!(dot.starts_with("digraph bdd {"));
+    assert!(dot.contains("label=\"a & \\\"b\\\"\""));
+    assert!(dot.contains("shape=circle, label=\"a\""));
+    assert!(dot.contains("shape=circle, label=\"b\""));
+    assert!(dot.contains("shape=box, label=\"T\""));
+    assert_eq!(dot.matches("style=dashed").count(), 2);
+    assert_eq!(dot.matches(" -> ").count(), 5);
+}
+
+// Test the parse tree renderings follow operator precedence
+#[test]
+fn test_ast_rendering() {
+    let formula = Formula::from("a & b | ~c");
+    assert_eq!(
+        formula.to_ascii_tree(),
+        "∨\n├─ ∧\n│  ├─ a\n│  └─ b\n└─ ¬\n   └─ c\n"
+    );
+    assert_eq!(Formula::from("a").to_ascii_tree(), "a\n");
+
+    let dot = Formula::from("a -> b <-> c").to_dot();
+    assert!(dot.contains("n0 [shape=ellipse, label=\"⇔\"]"));
+    assert!(dot.contains("n1 [shape=ellipse, label=\"⇒\"]"));
+    assert!(dot.contains("n0 -> n1;") && dot.contains("n0 -> n4;"));
+    assert!(dot.contains("n4 [shape=box, label=\"c\"]"));
+}
+
+// Test Quine–McCluskey finds minimal covers, including cyclic ones
+#[test]
+fn test_minimize() {
+    assert_eq!(Formula::from("a & b | a & ~b").minimize().to_string(), "a");
+    assert_eq!(Formula::from("a & ~a").minimize().to_string(), "F");
+    assert_eq!(Formula::from("a | ~a").minimize().to_string(), "T");
+    assert_eq!(
+        Formula::from("(a -> b) & (b -> c) & (c -> a) | ~a & b")
+            .minimize()
+            .to_string(),
+        "b ∧ c ∨ ¬a ∧ ¬c"
+    );
+
+    // The cyclic core of Σm(0, 1, 2, 5, 6, 7) needs three of its six primes
+    let cyclic = Formula::from(
+        "~a & ~b & ~c | ~a & ~b & c | ~a & b & ~c | a & ~b & c | a & b & ~c | a & b & c",
+    );
+    let minimized = cyclic.minimize();
+    assert_equivalent(&cyclic, &minimized);
+    assert_eq!(minimized.to_string().matches('∨').count(), 2);
+
+    for formula in random_formulas(0xdead_beef_cafe_f00d, 100) {
+        assert_equivalent(&formula, &formula.minimize());
+    }
+
+    // The cyclic core of a random 9-variable function is too large for
+    // Petrick's method, so it is covered greedily
+    let random = random_function(0x2545_f491_4f6c_dd1d, 9);
+    let dont_care = random_function(0x9e37_79b9_7f4a_7c15, 9);
+    assert_equivalent(&random, &random.minimize());
+    let minimized = random.minimize_with_dont_cares(&dont_care);
+    let care = |f: &Formula| Formula::from(f.root().clone() & !dont_care.root().clone());
+    assert_equivalent(&care(&minimized), &care(&random));
+}
+
+// Test the heuristic minimizer stays equivalent and handles many variables
+#[test]
+fn test_minimize_heuristic() {
+    let formula = Formula::from("a & b | a & ~b & c | ~a & c & d | b & c");
+    assert_eq!(
+        formula.minimize_heuristic(true).unwrap().to_string(),
+        "a ∧ b ∨ a ∧ c ∨ b ∧ c ∨ c ∧ d"
+    );
+
+    for formula in random_formulas(0x1357_9bdf_2468_ace0, 100) {
+        let heuristic = formula.minimize_heuristic(true).unwrap();
+        let exact = formula.minimize();
+        assert_equivalent(&formula, &heuristic);
+        assert!(heuristic.to_string().len() <= 2 * exact.to_string().len() + 4);
+    }
+
+    let pairs: Vec<_> = "abcdeghijklmnopqrsuvwxyz"
+        .as_bytes()
+        .chunks(2)
+        .map(|p| format!("{} & {}", p[0] as char, p[1] as char))
+        .collect();
+    let wide = Formula::from(pairs.join(" | ").as_str());
+    let minimized = wide.minimize_heuristic(false).unwrap();
+    assert_eq!(minimized.variables.len(), 24);
+    assert_eq!(minimized.to_string().matches('∨').count(), 11);
+    assert_eq!(minimized.to_string().matches('∧').count(), 12);
+
+    let error = MinimizeError::Mismatch {
+        assignment: vec![("a".to_string(), true), ("b".to_string(), false)],
+    };
+    assert_eq!(
+        error.to_string(),
+        "minimized formula differs from the original on a ¬b"
+    );
+
+    // Cubes hold one bit per variable, so 65 variables are refused
+    let names: Vec<_> = "abcdeghijklmnopqrsuvwxyz"
+        .chars()
+        .flat_map(|x| "abc".chars().map(move |y| format!("{}{}", x, y)))
+        .take(65)
+        .collect();
+    let wide = Formula::or_all(names.iter().map(|n| Formula::from(n.as_str())));
+    assert_eq!(
+        wide.minimize_heuristic(false).unwrap_err(),
+        MinimizeError::TooManyVariables(65)
+    );
+}
+
+// Test both minimizers exploit don't-cares and stay correct on the care set
+#[test]
+fn test_minimize_dont_cares() {
+    // Two of three inputs set, with all three set unreachable
+    let formula = Formula::from("a & b & ~c | a & ~b & c | ~a & b & c");
+    let dont_care = Formula::from("a & b & c");
+    assert_eq!(formula.minimize().to_string().matches('∧').count(), 6);
+    assert_eq!(
+        formula.minimize_with_dont_cares(&dont_care).to_string(),
+        "a ∧ b ∨ a ∧ c ∨ b ∧ c"
+    );
+    assert_eq!(
+        formula
+            .minimize_heuristic_with_dont_cares(&dont_care, true)
+            .unwrap()
+            .to_string(),
+        "a ∧ b ∨ a ∧ c ∨ b ∧ c"
+    );
+
+    // Rows 10 to 15 of a BCD digit never occur
+    let digit = Formula::from("a & ~b & ~c & d | ~a & b & c & d");
+    let unused = digit.select_rows(&[10, 11, 12, 13, 14, 15]).unwrap();
+    assert_eq!(unused.to_string().matches('∨').count(), 5);
+    let minimized = digit.minimize_with_dont_cares(&unused);
+    assert_eq!(minimized.to_string(), "a ∧ d ∨ b ∧ c ∧ d");
+    for (i, value) in digit.truth_vector().into_iter().enumerate().take(10) {
+        assert_eq!(minimized.truth_vector()[i], value);
+    }
+}
+
+// Test the CLI's choice of minimizer stays fast on random wide functions
+#[test]
+fn test_minimize_auto() {
+    // A random 8-input table with some don't-care rows, read as with
+    // `--from-csv`
+    let mut rng = Rng(0x6a09_e667_f3bc_c908);
+    let mut csv = String::from("a,b,c,d,e,g,h,i,out\n");
+    for row in 0..1 << 8 {
+        let inputs: Vec<_> = (0..8).rev().map(|j| (row >> j & 1).to_string()).collect();
+        let output = ["0", "1", "1", "0", "x"][rng.below(5) as usize];
+        csv.push_str(&format!("{},{}\n", inputs.join(","), output));
+    }
+    let spec = Specification::from_csv(&csv).unwrap();
+    let start = std::time::Instant::now();
+    let minimized = spec.formula.minimize_auto(&spec.dont_care).unwrap();
+    let random = random_function(0xbb67_ae85_84ca_a73b, 12);
+    let never = Formula::from("F");
+    let minimized_random = random.minimize_auto(&never).unwrap();
+    assert!(start.elapsed() < std::time::Duration::from_secs(10));
+
+    // The formulas are large, so compare compiled truth vectors
+    let variables = spec.formula.sorted_variables();
+    let expected = spec.formula.truth_vector_over(&variables);
+    let dont_care = spec.dont_care.truth_vector_over(&variables);
+    let actual = minimized.truth_vector_over(&variables);
+    for row in 0..1 << 8 {
+        assert!(dont_care[row] || actual[row] == expected[row]);
+    }
+    let variables = random.sorted_variables();
+    assert_eq!(
+        minimized_random.truth_vector_over(&variables),
+        random.truth_vector()
+    );
+
+    // Small functions are still minimized exactly
+    let formula = Formula::from("a & b & ~c | a & ~b & c | ~a & b & c");
+    let dont_care = Formula::from("a & b & c");
+    assert_eq!(
+        formula.minimize_auto(&dont_care).unwrap().to_string(),
+        "a ∧ b ∨ a ∧ c ∨ b ∧ c"
+    );
+}
+
+// Test Karnaugh maps use Gray-coded axes and overlay the minimal groups
+#[test]
+fn test_karnaugh_map() {
+    let formula = Formula::from("a & b | ~c & d");
+    let map = formula.karnaugh_map(None).unwrap();
+    assert_eq!((map.rows(), map.cols()), (4, 4));
+    let truth = formula.truth_vector();
+    for row in 0..4 {
+        for col in 0..4 {
+            let expected = if truth[map.minterm(row, col)] {
+                KarnaughCell::One
+            } else {
+                KarnaughCell::Zero
+            };
+            assert_eq!(map.cell(row, col), expected);
+            // Horizontal and vertical neighbours differ in a single variable
+            let right = map.minterm(row, (col + 1) % 4) ^ map.minterm(row, col);
+            let below = map.minterm((row + 1) % 4, col) ^ map.minterm(row, col);
+            assert_eq!((right.count_ones(), below.count_ones()), (1, 1));
+        }
+    }
+
+    let map = map.with_groups();
+    let groups: Vec<_> = map.groups().into_iter().map(|(_, term)| term).collect();
+    assert_eq!(groups, ["a ∧ b", "¬c ∧ d"]);
+    let text = map.to_string();
+    assert!(text.starts_with("ab\\cd │   00   01   11   10\n"));
+    assert!(text.contains("11 │  1:A 1:AB  1:A  1:A"));
+    assert!(map
+        .to_html()
+        .contains("<td class=\"one group-A group-B\">1</td>"));
+    assert!(map
+        .to_latex()
+        .contains("$ab \\backslash cd$ & 00 & 01 & 11 & 10"));
+
+    let dont_care = Formula::from("a & ~b");
+    let map = Formula::from("a & b")
+        .karnaugh_map(Some(&dont_care))
+        .unwrap();
+    assert_eq!(map.cell(1, 0), KarnaughCell::DontCare);
+    assert_eq!(map.with_groups().groups()[0].1, "a");
+
+    assert_eq!(
+        Formula::from("a").karnaugh_map(None).unwrap_err(),
+        KarnaughMapError { variables: 1 }
+    );
+}
+
+// Test synthesis from minterms, maxterms and truth vectors
+#[test]
+fn test_synthesis() {
+    let xor3 = Formula::from_truth_vector("01101001").unwrap();
+    assert_eq!(xor3.sorted_variables(), ["a", "b", "c"]);
+    assert_eq!(
+        xor3.truth_vector(),
+        [false, true, true, false, true, false, false, true]
+    );
+    let by_maxterms = Formula::from_maxterms(&["a", "b", "c"], &[0, 3, 5, 6]).unwrap();
+    assert_equivalent(&xor3, &by_maxterms);
+    let by_minterms = Formula::from_minterms(&["a", "b", "c"], &[1, 2, 4, 7]).unwrap();
+    assert_equivalent(&xor3, &by_minterms);
+
+    assert_eq!(
+        Formula::from_minterms(&["a", "b"], &[1, 4]).unwrap_err(),
+        TermOutOfRange {
+            index: 4,
+            variables: 2
+        }
+    );
+    assert!(Formula::from_maxterms(&["a"], &[2]).is_err());
+    assert!(Formula::from("a & b").select_rows(&[3, 4]).is_err());
+
+    assert_eq!(
+        Formula::from_truth_vector("011").unwrap_err(),
+        TruthVectorError::InvalidLength(3)
+    );
+    assert_eq!(
+        Formula::from_truth_vector("01x1").unwrap_err(),
+        TruthVectorError::InvalidCharacter('x')
+    );
+}
+
+// Test CSV specifications with don't-cares and their errors
+#[test]
+fn test_synthesis_csv() {
+    let spec = Specification::from_csv("a,b,out\n0,0,1\n1,1,X\n0,1,0\n1,0,1\n").unwrap();
+    assert_equivalent(&spec.formula, &Formula::from("~b"));
+    assert_eq!(spec.dont_care.truth_vector(), [false, false, false, true]);
+    let minimized = spec.formula.minimize_with_dont_cares(&spec.dont_care);
+    assert_eq!(minimized.to_string(), "¬b");
+
+    assert_eq!(
+        Specification::from_csv("a,b,out\n0,0\n").unwrap_err(),
+        CsvError::ColumnCount {
+            line: 2,
+            expected: 3,
+            found: 2
+        }
+    );
+    assert_eq!(
+        Specification::from_csv("a,out\nX,1\n").unwrap_err(),
+        CsvError::InvalidValue {
+            line: 2,
+            value: "X".to_string()
+        }
+    );
+    assert_eq!(
+        Specification::from_csv("a,out\n1,1\n\n1,0\n").unwrap_err(),
+        CsvError::ConflictingRow { line: 4 }
+    );
+    assert_eq!(
+        Specification::from_csv("out\n1\n").unwrap_err(),
+        CsvError::MissingHeader
+    );
+    assert_eq!(
+        Specification::from_csv("a,b,a,out\n0,0,0,1\n").unwrap_err(),
+        CsvError::DuplicateName("a".to_string())
+    );
+    assert_eq!(
+        Specification::from_csv("a,T,out\n0,0,1\n").unwrap_err(),
+        CsvError::InvalidName("T".to_string())
+    );
+    assert_eq!(
+        Specification::from_csv("a,x1,out\n0,0,1\n").unwrap_err(),
+        CsvError::InvalidName("x1".to_string())
+    );
+
+    // A row of 65 inputs would not fit in a row number
+    let names: Vec<_> = "abcdeghijklmnopqrsuvwxyz"
+        .chars()
+        .flat_map(|x| "abc".chars().map(move |y| format!("{}{}", x, y)))
+        .take(65)
+        .collect();
+    let csv = format!("{},out\n{}1\n", names.join(","), "1,".repeat(65));
+    assert_eq!(
+        Specification::from_csv(&csv).unwrap_err(),
+        CsvError::TooManyInputs(65)
+    );
+}
+
+// Test algebraic normal form matches the truth table
+#[test]
+fn test_anf() {
+    let or = Formula::from("a | b").to_anf();
+    assert_eq!(or.to_string(), "a ⊕ b ⊕ a·b");
+    assert_eq!(or.monomials(), [vec!["a"], vec!["b"], vec!["a", "b"]]);
+    assert_eq!(or.degree(), 2);
+    assert!(!or.is_affine());
+
+    let xnor = Formula::from("a <-> b").to_anf();
+    assert_eq!(xnor.to_string(), "1 ⊕ a ⊕ b");
+    assert!(xnor.constant() && xnor.is_affine() && !xnor.is_linear());
+    assert_eq!(Formula::from("a & ~a").to_anf().to_string(), "0");
+
+    for formula in random_formulas(40, 50) {
+        let anf = formula.to_anf();
+        assert_equivalent(&formula, &anf.to_formula());
+        assert!(anf.degree() <= formula.variables.len());
+    }
+}
+
+// Test the simplifier records each law it applies and stays equivalent
+#[test]
+fn test_simplify() {
+    let (simplified, steps) = Formula::from("~(a | ~b) | a & (a | c)").simplify_with_steps();
+    assert_eq!(simplified.to_string(), "¬a ∧ b ∨ a");
+    let laws: Vec<_> = steps.iter().map(|s| s.law).collect();
+    assert_eq!(laws, [Law::DeMorgan, Law::DoubleNegation, Law::Absorption]);
+    assert_eq!(steps[0].to_string(), "De Morgan: ¬(a ∨ ¬b) ⟶ ¬a ∧ ¬¬b");
+    assert_eq!(steps[2].before.to_string(), "a ∧ (a ∨ c)");
+
+    assert_eq!(Formula::from("(a -> F) & T").simplify().to_string(), "¬a");
+    assert_eq!(Formula::from("(a & ~a) <-> b").simplify().to_string(), "¬b");
+    assert_eq!(Formula::from("~T | a | a").simplify().to_string(), "a");
+
+    for formula in random_formulas(41, 100) {
+        let (simplified, steps) = formula.simplify_with_steps();
+        assert_equivalent(&formula, &simplified);
+        for step in steps {
+            let before = Formula::from_root(step.before);
+            assert_equivalent(&before, &Formula::from_root(step.after));
+        }
+        // Simplifying again finds nothing left to do
+        assert!(simplified.simplify_with_steps().1.is_empty());
+    }
+}
+
+// Test compiled evaluation agrees with evaluation by name
+#[test]
+fn test_compiled_formula() {
+    let formula = Formula::from("a & b | ~c -> d <-> e");
+    let compiled = formula.compile();
+    assert_eq!(compiled.variables(), ["a", "b", "c", "d", "e"]);
+    assert_eq!(compiled.index_of("c"), Some(2));
+    assert_eq!(compiled.index_of("z"), None);
+    assert!(CompiledFormula::new(&formula, &["a", "b"]).is_none());
+
+    for formula in random_formulas(42, 50) {
+        let variables = formula.sorted_variables();
+        let compiled = formula.compile();
+        let n = variables.len();
+        for row in 0..1u64 << n {
+            let values: Vec<bool> = (0..n).map(|j| row >> (n - 1 - j) & 1 == 1).collect();
+            let vars = variables
+                .iter()
+                .zip(&values)
+                .map(|(v, &b)| (v.to_string(), b))
+                .collect();
+            let expected = formula.eval(&vars).unwrap();
+            assert_eq!(compiled.eval(&values), expected);
+            assert_eq!(compiled.eval_bits(row), expected);
+        }
+    }
+}
+
+// Test bitsliced truth tables and classification agree with evaluation
+#[test]
+fn test_truth_bits() {
+    for formula in random_formulas(43, 50) {
+        let bits = formula.truth_bits();
+        assert_eq!(bits.iter().collect::<Vec<_>>(), formula.truth_vector());
+        let compiled = formula.compile();
+        for row in 0..bits.rows() {
+            assert_eq!(bits.get(row), compiled.eval_bits(row as u64));
+        }
+    }
+
+    // Variables past the sixth select whole words
+    let names: Vec<String> = ('a'..='z')
+        .filter(|&c| c != 'f' && c != 't')
+        .take(9)
+        .map(String::from)
+        .collect();
+    let source = names.join(" <-> ");
+    let parity = Formula::from(source.as_str());
+    let bits = parity.truth_bits();
+    assert_eq!(bits.words().len(), 8);
+    for row in 0..bits.rows() {
+        assert_eq!(bits.get(row), parity.compile().eval_bits(row as u64));
+    }
+    assert_eq!(parity.count_models(), 256);
+
+    assert_eq!(Formula::from("a | b").truth_bits().to_string(), "0111");
+    assert_eq!(
+        Formula::from("a | ~a").classify(),
+        Classification::Tautology
+    );
+    assert!(Formula::from("a & ~a").is_contradiction());
+    assert!(Formula::from("a -> b").is_satisfiable());
+    assert!(!Formula::from("a -> b").is_tautology());
+    assert_eq!(Formula::from("T").count_models(), 1);
+}
+
+// Test deeply nested formulas do not overflow the stack
+#[test]
+fn test_deep_chain() {
+    const DEPTH: usize = 1_000_000;
+
+    let negations = Formula::from(format!("{}a", "~".repeat(DEPTH)).as_str());
+    let vars = HashMap::from([("a".to_string(), true)]);
+    assert_eq!(negations.eval(&vars), Some(true));
+    assert_eq!(negations.sorted_variables(), ["a"]);
+    assert_eq!(negations.truth_vector(), [false, true]);
+    let text = negations.to_string();
+    assert_eq!(text.chars().count(), DEPTH + 1);
+    assert!(text.ends_with("¬¬a"));
+
+    // A left-deep chain of conjunctions, with a missing variable at the end
+    let source = "a & ".repeat(DEPTH) + "b";
+    let conjunction = Formula::from(source.as_str());
+    assert_eq!(conjunction.sorted_variables(), ["a", "b"]);
+    assert_eq!(conjunction.eval(&vars), None);
+    let vars = HashMap::from([("a".to_string(), false)]);
+    assert_eq!(conjunction.eval(&vars), Some(false));
+    assert_eq!(conjunction.count_models(), 1);
+    assert!(conjunction.to_string().starts_with("a ∧ a ∧ a"));
+
+    // So do the conversions and renderings
+    assert_eq!(negations.simplify().to_string(), "a");
+    assert_eq!(negations.to_nnf().to_string(), "a");
+    let mut manager = BddManager::new();
+    let bdd = negations.to_bdd(&mut manager);
+    assert_eq!(bdd, manager.var("a"));
+    assert_eq!(conjunction.simplify().to_string(), "a ∧ b");
+    assert_eq!(conjunction.to_nnf().root(), conjunction.root());
+    let shallow = Formula::from(format!("{}a", "~".repeat(2_000)).as_str());
+    let tree = shallow.to_ascii_tree();
+    assert_eq!(tree.lines().count(), 2_001);
+    assert_eq!(tree.lines().last().unwrap().trim_start(), "└─ a");
+
+    // Cloning, comparing and hashing use explicit stacks too
+    let copy = negations.root().clone();
+    assert_eq!(&copy, negations.root());
+    assert_ne!(&Node::not(copy), negations.root());
+    let copy = conjunction.root().clone();
+    assert_eq!(&copy, conjunction.root());
+    let set: HashSet<&Node> = [&copy, conjunction.root()].into_iter().collect();
+    assert_eq!(set.len(), 1);
+
+    // So does the debug representation
+    let debug = format!("{:?}", negations.root());
+    assert_eq!(debug.len(), 5 * DEPTH + 9);
+    assert_eq!(debug.find("Atom(\"a\")"), Some(4 * DEPTH));
+    assert!(debug.starts_with("Not(Not(") && debug.ends_with(")))"));
+
+    drop(negations);
+    drop(conjunction);
+}
+
+// Test the debug representation matches the derived one
+#[test]
+fn test_node_debug() {
+    let formula = Formula::from("a & ~T | (b -> c)");
+    let node = Node::iff(formula.root().clone(), Node::atom("say \"x\""));
+    assert_eq!(
+        format!("{:?}", node),
+        "Iff(Or(And(Atom(\"a\"), Not(Value(true))), If(Atom(\"b\"), Atom(\"c\"))), \
+         Atom(\"say \\\"x\\\"\"))"
+    );
+}
+
+// Test the DAG shares subformulas and evaluates like the tree
+#[test]
+fn test_dag() {
+    let mut dag = Dag::new();
+    let formula = Formula::from("(a & b | c) <-> ~(a & b | c)");
+    let root = formula.to_dag(&mut dag);
+    // a, b, a ∧ b, c, ∨, ¬ and ⇔
+    assert_eq!(dag.size(root), 7);
+    assert_eq!(dag.to_formula(root).to_string(), formula.to_string());
+    let again = Formula::from("~(a & b | c)").to_dag(&mut dag);
+    assert_eq!(dag.len(), 7);
+    assert!(matches!(dag.node(root), DagNode::Iff(_, r) if *r == again));
+
+    for formula in random_formulas(45, 50) {
+        let root = formula.to_dag(&mut dag);
+        assert_eq!(dag.to_node(root), formula.root);
+        let variables = formula.sorted_variables();
+        for row in 0..1usize << variables.len() {
+            let vars = variables
+                .iter()
+                .enumerate()
+                .map(|(j, v)| (v.to_string(), row >> j & 1 == 1))
+                .collect();
+            assert_eq!(dag.eval(root, &vars), formula.eval(&vars));
+        }
+    }
+
+    // Each level doubles the tree but adds one node to the graph
+    let mut dag = Dag::new();
+    let mut root = dag.add(DagNode::Atom("a".to_string()));
+    for _ in 0..100 {
+        let not = dag.add(DagNode::Not(root));
+        root = dag.add(DagNode::Iff(root, not));
+    }
+    assert_eq!(dag.size(root), 201);
+    let vars = HashMap::from([("a".to_string(), true)]);
+    assert_eq!(dag.eval(root, &vars), Some(false));
+    let b = dag.add(DagNode::Atom("b".to_string()));
+    let partial = dag.add(DagNode::And(root, b));
+    assert_eq!(dag.eval(partial, &vars), Some(false));
+    assert_eq!(
+        dag.eval_many(&[root, partial], &HashMap::new()),
+        [None, None]
+    );
+}
+
+// Test interned symbols are shared between formulas
+#[test]
+fn test_symbol_table() {
+    let mut symbols = SymbolTable::new();
+    let first = Formula::from("c & a").intern(&mut symbols);
+    let second = Formula::from("b | c").intern(&mut symbols);
+    assert_eq!(symbols.len(), 3);
+    assert_eq!(second[1], first[1]);
+    assert_eq!(symbols.name(first[0]), "a");
+    assert_eq!(symbols.get("b"), Some(second[0]));
+    assert_eq!(symbols.get("d"), None);
+    let names: Vec<_> = symbols.sorted().iter().map(|&s| symbols.name(s)).collect();
+    assert_eq!(names, ["a", "b", "c"]);
+
+    // Formulas compiled against one table share a value slice
+    let mut values = vec![false; symbols.len()];
+    values[symbols.get("b").unwrap().index()] = true;
+    let and = CompiledFormula::with_symbols(&Formula::from("c & a"), &symbols).unwrap();
+    let or = CompiledFormula::with_symbols(&Formula::from("b | c"), &symbols).unwrap();
+    assert!(!and.eval(&values));
+    assert!(or.eval(&values));
+    assert!(CompiledFormula::with_symbols(&Formula::from("d"), &symbols).is_none());
+
+    // Sorted symbols number rows as in the truth table
+    let sorted = symbols.sorted();
+    let formula = Formula::from("c & ~a | b");
+    let compiled = CompiledFormula::with_symbol_order(&formula, &symbols, &sorted).unwrap();
+    assert_eq!(compiled.variables(), ["a", "b", "c"]);
+    assert_eq!(compiled.truth_vector(), formula.truth_vector());
+    let partial = [symbols.get("a").unwrap(), symbols.get("c").unwrap()];
+    assert!(CompiledFormula::with_symbol_order(&formula, &symbols, &partial).is_none());
+
+    let mut manager = BddManager::with_symbols(&symbols);
+    for (i, name) in symbols.names().enumerate() {
+        assert_eq!(manager.var_index(name), i);
+    }
+}
+
+// Test counting and classification across several cubes
+#[test]
+fn test_cube_splitting() {
+    // 16 variables give 1024 words, several per cube
+    let names: Vec<String> = ('a'..='z')
+        .filter(|&c| c != 'f' && c != 't')
+        .take(16)
+        .map(String::from)
+        .collect();
+    let tautology = Formula::from(format!("{} | ~a", names.join(" | ")).as_str());
+    assert_eq!(tautology.classify(), Classification::Tautology);
+    let first_and_last = Formula::from(format!("a & {}", names[15]).as_str());
+    assert_eq!(first_and_last.count_models(), 1);
+
+    let chain = Formula::from(names.join(" & ").as_str());
+    assert_eq!(chain.classify(), Classification::Contingent);
+    assert_eq!(chain.count_models(), 1);
+    let bits = chain.truth_bits();
+    assert_eq!(bits.count_ones(), 1);
+    assert!(bits.get(bits.rows() - 1));
+    let negated = Formula::from(format!("~({})", names.join(" | ")).as_str());
+    assert!(negated.truth_bits().get(0));
+    assert_eq!(negated.count_models(), 1);
+}
+
+// Test syntax trees built in code match parsed ones
+#[test]
+fn test_node_builders() {
+    let (a, b, c) = (Node::atom("a"), Node::atom("b"), Node::atom("c"));
+    let built =
+        Formula::from(!(a.clone() & b.clone()) | Node::implies(c.clone(), Node::value(false)));
+    let parsed = Formula::from("~(a & b) | (c -> F)");
+    assert_eq!(built.root(), parsed.root());
+    assert_eq!(built.sorted_variables(), ["a", "b", "c"]);
+    assert_eq!(built.to_string(), "¬(a ∧ b) ∨ (c ⇒ F)");
+
+    let iff = Node::iff(a.clone(), Node::not(a));
+    assert_ne!(iff, Node::iff(b.clone(), Node::not(b)));
+    let set: HashSet<Node> = [iff.clone(), iff, c.clone(), c].into_iter().collect();
+    assert_eq!(set.len(), 2);
+}
+
+// Test visitors, folds and rewriters on the syntax tree
+#[test]
+fn test_visitor_and_fold() {
+    struct Trace(Vec<String>);
+
+    impl<'a> Visitor<'a> for Trace {
+        fn pre(&mut self, node: &'a Node) -> bool {
+            self.0.push(format!("+{}", node));
+            // Leave implications opaque
+            !matches!(node, Node::If(_, _))
+        }
+
+        fn post(&mut self, node: &'a Node) {
+            self.0.push(format!("-{}", node));
+        }
+    }
+
+    let formula = Formula::from("~a | (b -> c)");
+    let mut trace = Trace(Vec::new());
+    formula.root().walk(&mut trace);
+    assert_eq!(
+        trace.0,
+        [
+            "+¬a ∨ (b ⇒ c)",
+            "+¬a",
+            "+a",
+            "-a",
+            "-¬a",
+            "+b ⇒ c",
+            "-¬a ∨ (b ⇒ c)"
+        ]
+    );
+
+    struct Swap;
+
+    impl Fold for Swap {
+        fn fold_and(&mut self, left: Node, right: Node) -> Node {
+            Node::or(left, right)
+        }
+
+        fn fold_atom(&mut self, name: String) -> Node {
+            Node::Atom(name.to_uppercase())
+        }
+    }
+
+    let swapped = Formula::from("a & ~(b & c)").into_root().fold(&mut Swap);
+    assert_eq!(Formula::from(swapped).to_string(), "A ∨ ¬(B ∨ C)");
+
+    /// Replaces `¬¬x` by `x`.
+    struct DoubleNegation;
+
+    impl Rewriter for DoubleNegation {
+        fn rewrite(&mut self, node: Node) -> Node {
+            match &node {
+                Node::Not(inner) => match &**inner {
+                    Node::Not(x) => (**x).clone(),
+                    _ => node,
+                },
+                _ => node,
+            }
+        }
+    }
+
+    let source = format!("{}a", "~".repeat(100_001));
+    let folded = Formula::from(source.as_str())
+        .into_root()
+        .fold(&mut DoubleNegation);
+    assert_eq!(folded, Node::not(Node::atom("a")));
+}
+
+// Test substitution, renaming and balanced conjunctions
+#[test]
+fn test_substitute_and_compose() {
+    let template = Formula::from("a & b -> c");
+    let map = HashMap::from([
+        ("a".to_string(), Formula::from("b | d")),
+        ("b".to_string(), Formula::from("a")),
+    ]);
+    let substituted = template.substitute(&map);
+    assert_eq!(substituted.to_string(), "(b ∨ d) ∧ a ⇒ c");
+    assert_eq!(substituted.sorted_variables(), ["a", "b", "c", "d"]);
+
+    let swap = HashMap::from([
+        ("a".to_string(), "b".to_string()),
+        ("b".to_string(), "a".to_string()),
+    ]);
+    let renamed = Formula::from("a & ~b").rename(&swap);
+    assert_eq!(renamed.to_string(), "b ∧ ¬a");
+    let renamed = Formula::from("a").rename(&HashMap::from([("a".to_string(), "x".to_string())]));
+    assert_eq!(renamed.sorted_variables(), ["x"]);
+
+    let parts = ["a", "b", "c", "d", "e"].map(Formula::from);
+    let all = Formula::and_all(parts.clone());
+    assert_eq!(all.to_string(), "a ∧ b ∧ c ∧ d ∧ e");
+    assert_equivalent(&all, &Formula::from("a & b & c & d & e"));
+    assert_equivalent(&Formula::or_all(parts), &Formula::from("a | b | c | d | e"));
+    assert_eq!(Formula::and_all([]).to_string(), "T");
+    assert_eq!(Formula::or_all([]).to_string(), "F");
+
+    // A million operands nest about twenty levels deep
+    let atoms = (0..1_000_000).map(|i| Node::atom(if i % 2 == 0 { "a" } else { "b" }));
+    let many = Formula::or_all(atoms.map(Formula::from));
+    assert_eq!(many.sorted_variables(), ["a", "b"]);
+    struct Depth {
+        current: usize,
+        max: usize,
+    }
+
+    impl<'a> Visitor<'a> for Depth {
+        fn pre(&mut self, _: &'a Node) -> bool {
+            self.current += 1;
+            self.max = self.max.max(self.current);
+            true
+        }
+
+        fn post(&mut self, _: &'a Node) {
+            self.current -= 1;
+        }
+    }
+
+    let mut depth = Depth { current: 0, max: 0 };
+    many.root().walk(&mut depth);
+    assert_eq!(depth.max, 21);
+    let vars = HashMap::from([("a".to_string(), false), ("b".to_string(), true)]);
+    assert_eq!(many.eval(&vars), Some(true));
+}
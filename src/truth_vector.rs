@@ -0,0 +1,261 @@
+use std::{fmt, ops::Range};
+
+use crate::{
+    compiled::{CompiledFormula, Word},
+    Formula,
+};
+
+/// Bit `k` of this pattern is bit `p` of `k`, for the variables whose bit `p`
+/// of the row number falls inside a 64-row word.
+const LANES: [u64; 6] = [
+    0xAAAA_AAAA_AAAA_AAAA,
+    0xCCCC_CCCC_CCCC_CCCC,
+    0xF0F0_F0F0_F0F0_F0F0,
+    0xFF00_FF00_FF00_FF00,
+    0xFFFF_0000_FFFF_0000,
+    0xFFFF_FFFF_0000_0000,
+];
+
+/// Most variables a truth table may have, so that its rows can be numbered
+/// by `usize`.
+pub const MAX_TABLE_VARIABLES: usize = usize::BITS as usize - 1;
+
+/// The number of rows of a truth table over `num_vars` variables.
+///
+/// # Panics
+///
+/// Panics if `num_vars` exceeds [`MAX_TABLE_VARIABLES`].
+pub(crate) fn row_count(num_vars: usize) -> usize {
+    assert!(
+        num_vars <= MAX_TABLE_VARIABLES,
+        "truth tables support at most {} variables, found {}",
+        MAX_TABLE_VARIABLES,
+        num_vars
+    );
+    1 << num_vars
+}
+
+/// The full truth vector of a formula as a bitset: row `i` is bit `i % 64` of
+/// word `i / 64`, with rows numbered as in [`Formula::truth_vector`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TruthVector {
+    num_vars: usize,
+    words: Vec<u64>,
+}
+
+impl TruthVector {
+    pub fn num_vars(&self) -> usize {
+        self.num_vars
+    }
+
+    /// The number of rows, `2^n`.
+    pub fn rows(&self) -> usize {
+        row_count(self.num_vars)
+    }
+
+    /// The rows packed 64 to a word; unused bits of the last word are clear.
+    pub fn words(&self) -> &[u64] {
+        &self.words
+    }
+
+    pub fn get(&self, row: usize) -> bool {
+        self.words[row / 64] >> (row % 64) & 1 == 1
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = bool> + '_ {
+        (0..self.rows()).map(|row| self.get(row))
+    }
+
+    /// The number of true rows.
+    pub fn count_ones(&self) -> u64 {
+        self.words.iter().map(|w| u64::from(w.count_ones())).sum()
+    }
+
+    pub fn all(&self) -> bool {
+        self.count_ones() == self.rows() as u64
+    }
+
+    pub fn any(&self) -> bool {
+        self.words.iter().any(|&w| w != 0)
+    }
+}
+
+/// Prints the rows in order as `0` and `1`, the inverse of
+/// [`Formula::from_truth_vector`].
+impl fmt::Display for TruthVector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for value in self.iter() {
+            write!(f, "{}", if value { '1' } else { '0' })?;
+        }
+        Ok(())
+    }
+}
+
+/// The row space is split into up to `1 << SPLIT_BITS` cubes, each fixing the
+/// first variables and covering a contiguous run of words.
+const SPLIT_BITS: u32 = 8;
+
+impl CompiledFormula {
+    fn num_words(&self) -> usize {
+        (row_count(self.variables().len()) >> 6).max(1)
+    }
+
+    /// The bits of the last word that hold rows.
+    fn last_word_mask(&self) -> u64 {
+        let rows = row_count(self.variables().len());
+        if rows < 64 {
+            (1 << rows) - 1
+        } else {
+            u64::MAX
+        }
+    }
+
+    /// Evaluates the 64 rows of word `w`: each variable becomes the word of
+    /// its values over those rows and each operator a bitwise operation.
+    fn word(&self, w: usize, stack: &mut [u64]) -> u64 {
+        let n = self.variables().len();
+        let word = self.run(
+            |i| {
+                let bit = n - 1 - i;
+                match LANES.get(bit) {
+                    Some(&lanes) => lanes,
+                    None => u64::splat(w >> (bit - 6) & 1 == 1),
+                }
+            },
+            stack,
+        );
+        word & self.last_word_mask()
+    }
+
+    /// Applies `f` to the word range of every cube, in order. With the
+    /// `parallel` feature the cubes are spread across threads.
+    fn map_cubes<T: Send>(&self, f: impl Fn(Range<usize>, &mut [u64]) -> T + Sync) -> Vec<T> {
+        let num_words = self.num_words();
+        let per_cube = (num_words >> SPLIT_BITS).max(1);
+        let cubes = (0..num_words)
+            .step_by(per_cube)
+            .map(|start| start..start + per_cube);
+
+        #[cfg(feature = "parallel")]
+        {
+            use rayon::prelude::*;
+
+            let cubes: Vec<_> = cubes.collect();
+            cubes
+                .into_par_iter()
+                .map_init(|| vec![0; self.depth()], |stack, cube| f(cube, stack))
+                .collect()
+        }
+        #[cfg(not(feature = "parallel"))]
+        {
+            let mut stack = vec![0; self.depth()];
+            cubes.map(|cube| f(cube, &mut stack)).collect()
+        }
+    }
+
+    /// Evaluates every row, 64 at a time.
+    ///
+    /// # Panics
+    ///
+    /// This and the other whole-table methods panic if there are more than
+    /// [`MAX_TABLE_VARIABLES`] variables.
+    pub fn truth_bits(&self) -> TruthVector {
+        let words = self
+            .map_cubes(|cube, stack| cube.map(|w| self.word(w, stack)).collect::<Vec<_>>())
+            .concat();
+        TruthVector {
+            num_vars: self.variables().len(),
+            words,
+        }
+    }
+
+    /// The number of satisfying rows, counted without storing the table.
+    pub fn count_models(&self) -> u64 {
+        self.map_cubes(|cube, stack| {
+            cube.map(|w| u64::from(self.word(w, stack).count_ones()))
+                .sum::<u64>()
+        })
+        .into_iter()
+        .sum()
+    }
+
+    /// Classifies the formula without storing the table. Each cube stops as
+    /// soon as it has seen both a true and a false row.
+    pub fn classify(&self) -> Classification {
+        let full = self.last_word_mask();
+        let (any_true, any_false) = self
+            .map_cubes(|cube, stack| {
+                let (mut any_true, mut any_false) = (false, false);
+                for w in cube {
+                    let word = self.word(w, stack);
+                    any_true |= word != 0;
+                    any_false |= word != full;
+                    if any_true && any_false {
+                        break;
+                    }
+                }
+                (any_true, any_false)
+            })
+            .into_iter()
+            .fold((false, false), |(t, f), (ct, cf)| (t || ct, f || cf));
+        match (any_true, any_false) {
+            (true, false) => Classification::Tautology,
+            (false, _) => Classification::Contradiction,
+            (true, true) => Classification::Contingent,
+        }
+    }
+}
+
+/// Whether a formula holds on every row, no row or some of them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Classification {
+    Tautology,
+    Contradiction,
+    Contingent,
+}
+
+impl fmt::Display for Classification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Classification::Tautology => "tautology",
+            Classification::Contradiction => "contradiction",
+            Classification::Contingent => "contingent",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl Formula {
+    /// Computes the truth vector as a bitset, 64 rows per pass and in
+    /// parallel with the `parallel` feature.
+    ///
+    /// # Panics
+    ///
+    /// This and the other whole-table methods, such as
+    /// [`Formula::count_models`] and [`Formula::classify`], panic if the
+    /// formula has more than [`MAX_TABLE_VARIABLES`] variables.
+    pub fn truth_bits(&self) -> TruthVector {
+        self.compile().truth_bits()
+    }
+
+    /// The number of satisfying rows of the truth table.
+    pub fn count_models(&self) -> u64 {
+        self.compile().count_models()
+    }
+
+    pub fn classify(&self) -> Classification {
+        self.compile().classify()
+    }
+
+    pub fn is_tautology(&self) -> bool {
+        self.classify() == Classification::Tautology
+    }
+
+    pub fn is_contradiction(&self) -> bool {
+        self.classify() == Classification::Contradiction
+    }
+
+    pub fn is_satisfiable(&self) -> bool {
+        self.classify() != Classification::Contradiction
+    }
+}
@@ -0,0 +1,202 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    visit::{Polarities, Polarity},
+    Cnf, Dag, DagNode, Formula, Literal, Models, Node, NodeId, Visitor,
+};
+
+/// An equisatisfiable CNF encoding of a [`Formula`].
+///
+/// Every binary operator and constant gets a fresh auxiliary variable, so the
+/// CNF grows linearly with the formula. Projecting a model of the CNF onto
+/// `variables` gives a model of the original formula and vice versa.
+#[derive(Clone, Debug)]
+pub struct Tseitin {
+    pub cnf: Cnf,
+    /// Every subformula of the original formula, with shared ones stored
+    /// once.
+    pub dag: Dag,
+    /// The subformula each auxiliary variable stands for, as a handle into
+    /// `dag`.
+    pub auxiliary: HashMap<String, NodeId>,
+    /// The variables of the original formula.
+    pub variables: HashSet<String>,
+}
+
+impl Tseitin {
+    /// The subformula the auxiliary variable `name` stands for, rebuilt from
+    /// `dag`.
+    pub fn definition(&self, name: &str) -> Option<Formula> {
+        let &id = self.auxiliary.get(name)?;
+        Some(self.dag.to_formula(id))
+    }
+
+    /// Restricts a model of the CNF to the original variables.
+    pub fn project(&self, model: &HashMap<String, bool>) -> HashMap<String, bool> {
+        model
+            .iter()
+            .filter(|(name, _)| self.variables.contains(*name))
+            .map(|(name, &value)| (name.clone(), value))
+            .collect()
+    }
+
+    /// Enumerates the models of the CNF, projected onto the original
+    /// variables.
+    pub fn models(&self) -> Models {
+        let mut variables: Vec<_> = self.variables.iter().map(String::as_str).collect();
+        variables.sort_unstable();
+        Models::from_cnf(&self.cnf, &variables)
+    }
+}
+
+struct Encoder<'a> {
+    polarity_aware: bool,
+    variables: &'a HashSet<String>,
+    clauses: Vec<Vec<Literal>>,
+    dag: Dag,
+    auxiliary: HashMap<String, NodeId>,
+    polarities: Polarities<'a>,
+    /// The literal and DAG handle of each encoded operand.
+    encoded: Vec<(Literal, NodeId)>,
+}
+
+impl Encoder<'_> {
+    /// A fresh auxiliary variable standing for the subformula `id`.
+    ///
+    /// The names contain an underscore and a digit, so the parser never
+    /// produces them, and they skip the formula's own variables.
+    fn fresh(&mut self, id: NodeId) -> Literal {
+        let mut name = format!("_t{}", self.auxiliary.len());
+        while self.variables.contains(&name) {
+            name.insert(0, '_');
+        }
+        self.auxiliary.insert(name.clone(), id);
+        Literal::new(name, true)
+    }
+
+    /// Emits the clauses defining `x` as `node`, for the requested polarities.
+    ///
+    /// `implies` holds the clauses for `x ⇒ node` and `implied` those for
+    /// `node ⇒ x`. Plaisted–Greenbaum keeps only the directions needed by the
+    /// polarity the subformula occurs with.
+    fn define(
+        &mut self,
+        [positive, negative]: Polarity,
+        implies: Vec<Vec<Literal>>,
+        implied: Vec<Vec<Literal>>,
+    ) {
+        if positive || !self.polarity_aware {
+            self.clauses.extend(implies);
+        }
+        if negative || !self.polarity_aware {
+            self.clauses.extend(implied);
+        }
+    }
+}
+
+/// Encodes each node once its operands are, leaving its literal on
+/// `encoded`. Each subformula is also added to the DAG, so an auxiliary
+/// variable refers to its definition by handle instead of holding a copy of
+/// it.
+impl<'a> Visitor<'a> for Encoder<'a> {
+    fn pre(&mut self, node: &'a Node) -> bool {
+        self.polarities.enter(node);
+        true
+    }
+
+    fn post(&mut self, node: &'a Node) {
+        let polarity = self.polarities.exit();
+        let (lit, id) = match node {
+            Node::Atom(name) => {
+                let id = self.dag.add(DagNode::Atom(name.clone()));
+                (Literal::new(name.clone(), true), id)
+            }
+            Node::Not(_) => {
+                let (a, operand) = self.encoded.pop().unwrap();
+                (!a, self.dag.add(DagNode::Not(operand)))
+            }
+            Node::Value(b) => {
+                let id = self.dag.add(DagNode::Value(*b));
+                let x = self.fresh(id);
+                let unit = if *b { x.clone() } else { !x.clone() };
+                self.clauses.push(vec![unit]);
+                (x, id)
+            }
+            _ => {
+                let (b, right) = self.encoded.pop().unwrap();
+                let (a, left) = self.encoded.pop().unwrap();
+                let id = self.dag.add(match node {
+                    Node::And(_, _) => DagNode::And(left, right),
+                    Node::Or(_, _) => DagNode::Or(left, right),
+                    Node::If(_, _) => DagNode::If(left, right),
+                    _ => DagNode::Iff(left, right),
+                });
+                let x = self.fresh(id);
+                let (implies, implied) = match node {
+                    Node::And(_, _) => (
+                        vec![vec![!x.clone(), a.clone()], vec![!x.clone(), b.clone()]],
+                        vec![vec![x.clone(), !a, !b]],
+                    ),
+                    Node::Or(_, _) => (
+                        vec![vec![!x.clone(), a.clone(), b.clone()]],
+                        vec![vec![x.clone(), !a], vec![x.clone(), !b]],
+                    ),
+                    Node::If(_, _) => (
+                        vec![vec![!x.clone(), !a.clone(), b.clone()]],
+                        vec![vec![x.clone(), a], vec![x.clone(), !b]],
+                    ),
+                    _ => (
+                        vec![
+                            vec![!x.clone(), !a.clone(), b.clone()],
+                            vec![!x.clone(), a.clone(), !b.clone()],
+                        ],
+                        vec![
+                            vec![x.clone(), a.clone(), b.clone()],
+                            vec![x.clone(), !a, !b],
+                        ],
+                    ),
+                };
+                self.define(polarity, implies, implied);
+                (x, id)
+            }
+        };
+        self.encoded.push((lit, id));
+    }
+}
+
+impl Formula {
+    fn encode(&self, polarity_aware: bool) -> Tseitin {
+        let mut encoder = Encoder {
+            polarity_aware,
+            variables: &self.variables,
+            clauses: Vec::new(),
+            dag: Dag::new(),
+            auxiliary: HashMap::new(),
+            polarities: Polarities::default(),
+            encoded: Vec::new(),
+        };
+        self.root.walk(&mut encoder);
+        let (root, _) = encoder.encoded.pop().unwrap();
+        encoder.clauses.push(vec![root]);
+        Tseitin {
+            cnf: Cnf {
+                clauses: encoder.clauses,
+            },
+            dag: encoder.dag,
+            auxiliary: encoder.auxiliary,
+            variables: self.variables.clone(),
+        }
+    }
+
+    /// Encodes the formula as an equisatisfiable CNF of linear size, with
+    /// full equivalence definitions for every auxiliary variable.
+    pub fn to_tseitin(&self) -> Tseitin {
+        self.encode(false)
+    }
+
+    /// Like [`Formula::to_tseitin`], but only emits the implication directions
+    /// required by the polarity each subformula occurs with.
+    pub fn to_plaisted_greenbaum(&self) -> Tseitin {
+        self.encode(true)
+    }
+}
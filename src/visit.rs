@@ -0,0 +1,231 @@
+use crate::Node;
+
+/// A read-only traversal of a syntax tree, driven by [`Node::walk`].
+///
+/// Operands are visited left to right.
+pub trait Visitor<'a> {
+    /// Called before the operands of `node`. Returning `false` skips the
+    /// operands and the matching [`post`](Visitor::post) call.
+    fn pre(&mut self, node: &'a Node) -> bool {
+        let _ = node;
+        true
+    }
+
+    /// Called after the operands of `node`.
+    fn post(&mut self, node: &'a Node) {
+        let _ = node;
+    }
+}
+
+/// An owning bottom-up transform of a syntax tree, driven by [`Node::fold`].
+///
+/// Each method receives the node's operands already folded. The defaults
+/// rebuild the node unchanged, so implementations override only the cases
+/// they care about.
+pub trait Fold {
+    fn fold_atom(&mut self, name: String) -> Node {
+        Node::Atom(name)
+    }
+
+    fn fold_value(&mut self, value: bool) -> Node {
+        Node::Value(value)
+    }
+
+    fn fold_not(&mut self, operand: Node) -> Node {
+        Node::not(operand)
+    }
+
+    fn fold_and(&mut self, left: Node, right: Node) -> Node {
+        Node::and(left, right)
+    }
+
+    fn fold_or(&mut self, left: Node, right: Node) -> Node {
+        Node::or(left, right)
+    }
+
+    fn fold_if(&mut self, left: Node, right: Node) -> Node {
+        Node::implies(left, right)
+    }
+
+    fn fold_iff(&mut self, left: Node, right: Node) -> Node {
+        Node::iff(left, right)
+    }
+}
+
+/// A bottom-up transform applied to every rebuilt node, for rewrites that do
+/// not depend on the kind of node.
+pub trait Rewriter {
+    /// Rewrites `node`, whose operands have already been rewritten.
+    fn rewrite(&mut self, node: Node) -> Node;
+}
+
+impl<R: Rewriter> Fold for R {
+    fn fold_atom(&mut self, name: String) -> Node {
+        self.rewrite(Node::Atom(name))
+    }
+
+    fn fold_value(&mut self, value: bool) -> Node {
+        self.rewrite(Node::Value(value))
+    }
+
+    fn fold_not(&mut self, operand: Node) -> Node {
+        self.rewrite(Node::not(operand))
+    }
+
+    fn fold_and(&mut self, left: Node, right: Node) -> Node {
+        self.rewrite(Node::and(left, right))
+    }
+
+    fn fold_or(&mut self, left: Node, right: Node) -> Node {
+        self.rewrite(Node::or(left, right))
+    }
+
+    fn fold_if(&mut self, left: Node, right: Node) -> Node {
+        self.rewrite(Node::implies(left, right))
+    }
+
+    fn fold_iff(&mut self, left: Node, right: Node) -> Node {
+        self.rewrite(Node::iff(left, right))
+    }
+}
+
+/// The polarities a subformula occurs with: index 0 as itself, index 1
+/// negated.
+pub(crate) type Polarity = [bool; 2];
+
+/// Tracks the polarity of every node during a [`Node::walk`] whose
+/// [`pre`](Visitor::pre) never skips operands. Call
+/// [`enter`](Polarities::enter) from `pre` and [`exit`](Polarities::exit)
+/// from `post`.
+#[derive(Default)]
+pub(crate) struct Polarities<'a> {
+    /// The entered nodes with their polarity and how many of their operands
+    /// have been entered, innermost last.
+    entered: Vec<(&'a Node, Polarity, usize)>,
+}
+
+impl<'a> Polarities<'a> {
+    /// Enters `node`, returning its polarity. The root occurs positively.
+    pub(crate) fn enter(&mut self, node: &'a Node) -> Polarity {
+        let polarity = match self.entered.last_mut() {
+            None => [true, false],
+            Some((parent, [pos, neg], operands)) => {
+                *operands += 1;
+                match parent {
+                    // ¬a and the antecedent of a ⇒ b flip the polarity
+                    Node::Not(_) => [*neg, *pos],
+                    Node::If(_, _) if *operands == 1 => [*neg, *pos],
+                    // The operands of ⇔ occur both ways
+                    Node::Iff(_, _) => [*pos || *neg; 2],
+                    _ => [*pos, *neg],
+                }
+            }
+        };
+        self.entered.push((node, polarity, 0));
+        polarity
+    }
+
+    /// Leaves the node entered last, returning its polarity.
+    pub(crate) fn exit(&mut self) -> Polarity {
+        self.entered.pop().unwrap().1
+    }
+}
+
+/// Moves a boxed operand out, leaving a leaf behind.
+pub(crate) fn take(operand: &mut Node) -> Node {
+    std::mem::replace(operand, Node::Value(false))
+}
+
+impl Node {
+    /// Walks the tree in depth-first order, calling `visitor` on every node.
+    ///
+    /// This and [`Node::fold`] keep their own stacks instead of recursing, so
+    /// deep formulas cannot overflow the call stack.
+    pub fn walk<'a, V: Visitor<'a> + ?Sized>(&'a self, visitor: &mut V) {
+        let mut pending = vec![(self, false)];
+        while let Some((node, expanded)) = pending.pop() {
+            if expanded {
+                visitor.post(node);
+                continue;
+            }
+            if !visitor.pre(node) {
+                continue;
+            }
+            pending.push((node, true));
+            match node {
+                Node::And(left, right)
+                | Node::Or(left, right)
+                | Node::If(left, right)
+                | Node::Iff(left, right) => {
+                    pending.push((right, false));
+                    pending.push((left, false));
+                }
+                Node::Not(operand) => pending.push((operand, false)),
+                Node::Atom(_) | Node::Value(_) => {}
+            }
+        }
+    }
+
+    /// Transforms the tree bottom-up with `folder`, consuming it. Like
+    /// [`Node::walk`], it works on formulas of any depth.
+    pub fn fold<F: Fold + ?Sized>(self, folder: &mut F) -> Node {
+        enum Frame {
+            Enter(Node),
+            Not,
+            And,
+            Or,
+            If,
+            Iff,
+        }
+
+        let mut frames = vec![Frame::Enter(self)];
+        let mut folded: Vec<Node> = Vec::new();
+        while let Some(frame) = frames.pop() {
+            let mut node = match frame {
+                Frame::Enter(node) => node,
+                Frame::Not => {
+                    let operand = folded.pop().unwrap();
+                    folded.push(folder.fold_not(operand));
+                    continue;
+                }
+                frame => {
+                    let right = folded.pop().unwrap();
+                    let left = folded.pop().unwrap();
+                    folded.push(match frame {
+                        Frame::And => folder.fold_and(left, right),
+                        Frame::Or => folder.fold_or(left, right),
+                        Frame::If => folder.fold_if(left, right),
+                        _ => folder.fold_iff(left, right),
+                    });
+                    continue;
+                }
+            };
+            // `Node` implements `Drop`, so operands are moved out in place
+            let (kind, operands) = match &mut node {
+                Node::Atom(name) => {
+                    folded.push(folder.fold_atom(std::mem::take(name)));
+                    continue;
+                }
+                Node::Value(b) => {
+                    folded.push(folder.fold_value(*b));
+                    continue;
+                }
+                Node::Not(operand) => {
+                    frames.push(Frame::Not);
+                    frames.push(Frame::Enter(take(operand)));
+                    continue;
+                }
+                Node::And(l, r) => (Frame::And, (l, r)),
+                Node::Or(l, r) => (Frame::Or, (l, r)),
+                Node::If(l, r) => (Frame::If, (l, r)),
+                Node::Iff(l, r) => (Frame::Iff, (l, r)),
+            };
+            let (left, right) = operands;
+            let (left, right) = (take(left), take(right));
+            frames.push(kind);
+            frames.push(Frame::Enter(right));
+            frames.push(Frame::Enter(left));
+        }
+        folded.pop().unwrap()
+    }
+}